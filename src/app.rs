@@ -1,14 +1,27 @@
 use crate::{
-    db::client::{count_documents, fetch_documents},
+    db::{
+        client::{count_documents, fetch_documents},
+        copy::CopyJob,
+        indexes::IndexInfo,
+        ops::OperationFilter,
+        profiler::{ProfileEntry, ProfileSort, ProfileStatus},
+        rename::RenameJob,
+        staging::{StagedChange, stage_change},
+        truncate::TruncateJob,
+        views::ViewInfo,
+    },
     theme::ThemeName,
-    tui::filepicker::FilePickerState,
+    tui::{dashboard::Dashboard, filepicker::FilePickerState, form::FormState},
 };
 use crossterm::event::KeyEvent;
 use mongodb::{
     Client,
-    bson::{Document, oid::ObjectId},
+    bson::{Bson, Document, oid::ObjectId},
+    options::UpdateModifications,
 };
 use std::collections::{HashMap, HashSet};
+use std::path::PathBuf;
+use std::time::Instant;
 use unicode_segmentation::UnicodeSegmentation;
 #[derive(PartialEq)]
 pub enum AppMode {
@@ -37,7 +50,9 @@ pub enum InputContext {
     Uri,
     ConnectionName,
     CollectionName,
+    DatabaseName,
     SearchCollections,
+    CloneTargetCollection,
     None,
 }
 
@@ -79,14 +94,46 @@ pub enum PendingDeletion {
         id: ObjectId,
         name: String,
     },
+    Index {
+        db: String,
+        collection: String,
+        name: String,
+    },
+    User {
+        db: String,
+        name: String,
+    },
+    /// Empties a collection but keeps its indexes and options.
+    AllDocuments {
+        db: String,
+        collection: String,
+    },
 }
 
 pub struct PendingBulkUpdate {
     pub db: String,
     pub collection: String,
     pub filter: Document,
-    pub update: Document,
+    pub update: UpdateModifications,
     pub matched_count: u64,
+    /// Field path and target type when the update is a `$convert` refactor.
+    pub conversion: Option<(String, String)>,
+}
+
+pub struct PendingValidation {
+    pub db: String,
+    pub collection: String,
+    /// `validator`, `validationLevel` and `validationAction` for `collMod`.
+    pub rules: Document,
+}
+
+impl PendingValidation {
+    pub fn confirmation_message(&self) -> String {
+        format!(
+            "Apply validation rules to {}.{}? [y/N, v: find violators]",
+            self.db, self.collection
+        )
+    }
 }
 
 pub struct PendingBulkDeletion {
@@ -96,6 +143,89 @@ pub struct PendingBulkDeletion {
     pub matched_count: u64,
 }
 
+pub struct PendingClone {
+    pub document: Document,
+    pub db: String,
+    pub collection: String,
+    pub picking_target: bool,
+    pub target_db: Option<String>,
+}
+
+impl PendingClone {
+    pub fn confirmation_message(&self) -> String {
+        format!(
+            "Insert copy into {}.{}? [y/N, t: choose another target]",
+            self.db, self.collection
+        )
+    }
+}
+
+pub struct PendingKillOp {
+    pub opid: Bson,
+    pub ns: String,
+}
+
+impl PendingKillOp {
+    pub fn confirmation_message(&self) -> String {
+        let ns = if self.ns.is_empty() { "-" } else { &self.ns };
+        format!("Kill operation {} on {ns}? [y/N]", self.opid)
+    }
+}
+
+pub struct PendingDatabaseRename {
+    pub uri: String,
+    pub db: String,
+    pub new_name: String,
+}
+
+impl PendingDatabaseRename {
+    pub fn confirmation_message(&self) -> String {
+        format!(
+            "Rename database '{}' to '{}'? Collections are moved, counts checked, then '{}' is dropped. [y/N]",
+            self.db, self.new_name, self.db
+        )
+    }
+}
+
+/// A write whose documents fail a validator that only warns; the server
+/// accepts it, so it is sent once the user confirms.
+pub enum WarnedWrite {
+    Update {
+        db: String,
+        collection: String,
+        original: Document,
+        edited: Document,
+    },
+    Insert {
+        db: String,
+        collection: String,
+        document: Document,
+    },
+    /// Documents per collection, from import files that failed pre-flight.
+    Import {
+        db: String,
+        batches: Vec<(String, Vec<Document>)>,
+    },
+}
+
+impl WarnedWrite {
+    pub fn confirmation_message(&self) -> String {
+        "The validator only warns about these violations. Write anyway? [y/N]".to_string()
+    }
+}
+
+/// A collection or database copy waiting for its target to be picked in the tree.
+pub struct PendingCopy {
+    /// Kept so the target can be on another connection.
+    pub source: Client,
+    pub uri: String,
+    pub db: String,
+    /// `None` copies every collection of the database.
+    pub collection: Option<String>,
+    /// Filter text to prefill, taken from the loaded collection.
+    pub filter: String,
+}
+
 impl PendingBulkDeletion {
     pub fn confirmation_message(&self) -> String {
         format!(
@@ -108,12 +238,134 @@ impl PendingBulkDeletion {
 impl PendingBulkUpdate {
     pub fn confirmation_message(&self) -> String {
         format!(
-            "Update {} document(s) matching this filter? [y/N]",
+            "Update {} document(s) matching this filter? [y/N, p: preview]",
             self.matched_count
         )
     }
 }
 
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PanelTone {
+    Normal,
+    Header,
+    Added,
+    Removed,
+    Muted,
+}
+
+pub struct PanelLine {
+    pub text: String,
+    pub tone: PanelTone,
+}
+
+impl PanelLine {
+    pub fn new(tone: PanelTone, text: impl Into<String>) -> Self {
+        Self {
+            text: text.into(),
+            tone,
+        }
+    }
+}
+
+/// What an info panel shows, for panels whose rows can be acted on.
+pub enum PanelKind {
+    Info,
+    Indexes {
+        db: String,
+        collection: String,
+        indexes: Vec<IndexInfo>,
+    },
+    Schema {
+        db: String,
+        collection: String,
+        json_schema: Document,
+    },
+    /// `opids` and `namespaces` follow row order.
+    Operations {
+        filter: OperationFilter,
+        opids: Vec<Bson>,
+        namespaces: Vec<String>,
+        paused: bool,
+        refreshed_at: Instant,
+    },
+    Profile {
+        db: String,
+        status: ProfileStatus,
+        entries: Vec<ProfileEntry>,
+        sort: ProfileSort,
+        grouped: bool,
+    },
+    /// `users` holds the user names in row order.
+    Users {
+        db: String,
+        users: Vec<String>,
+    },
+    /// Import violations; the CSV/TSV `files` are mapped once it is closed.
+    ImportReport {
+        uri: String,
+        db: String,
+        files: Vec<PathBuf>,
+    },
+}
+
+pub struct InfoPanel {
+    pub title: String,
+    pub lines: Vec<PanelLine>,
+    pub scroll: usize,
+    pub kind: PanelKind,
+    /// Line numbers of the selectable rows, in selection order.
+    pub items: Vec<usize>,
+    pub selected: usize,
+}
+
+impl InfoPanel {
+    pub fn new(title: impl Into<String>, lines: Vec<PanelLine>) -> Self {
+        Self {
+            title: title.into(),
+            lines,
+            scroll: 0,
+            kind: PanelKind::Info,
+            items: Vec::new(),
+            selected: 0,
+        }
+    }
+
+    pub fn selectable(
+        title: impl Into<String>,
+        lines: Vec<PanelLine>,
+        kind: PanelKind,
+        items: Vec<usize>,
+    ) -> Self {
+        Self {
+            kind,
+            items,
+            ..Self::new(title, lines)
+        }
+    }
+
+    pub fn selected_line(&self) -> Option<usize> {
+        self.items.get(self.selected).copied()
+    }
+
+    pub fn select_next(&mut self) {
+        if self.selected + 1 < self.items.len() {
+            self.selected += 1;
+        }
+    }
+
+    pub fn select_previous(&mut self) {
+        self.selected = self.selected.saturating_sub(1);
+    }
+
+    pub fn scroll_down(&mut self, amount: usize) {
+        self.scroll = (self.scroll + amount).min(self.lines.len().saturating_sub(1));
+    }
+
+    pub fn scroll_up(&mut self, amount: usize) {
+        self.scroll = self.scroll.saturating_sub(amount);
+    }
+}
+
 impl PendingDeletion {
     pub fn confirmation_message(&self) -> String {
         let target = match self {
@@ -121,6 +373,11 @@ impl PendingDeletion {
             Self::Database { name, .. } => format!("database '{name}'"),
             Self::Document { .. } => "selected document".to_string(),
             Self::Field { name, .. } => format!("field '{name}'"),
+            Self::Index { name, .. } => format!("index '{name}'"),
+            Self::User { db, name } => format!("user '{name}@{db}'"),
+            Self::AllDocuments { db, collection } => {
+                format!("all documents in '{db}.{collection}' (indexes and options are kept)")
+            }
         };
 
         format!("Are you sure you want to delete {target}? [y/N]")
@@ -191,6 +448,28 @@ pub struct AppState {
     pub pending_deletion: Option<PendingDeletion>,
     pub pending_bulk_update: Option<PendingBulkUpdate>,
     pub pending_bulk_deletion: Option<PendingBulkDeletion>,
+    pub pending_clone: Option<PendingClone>,
+    pub pending_validation: Option<PendingValidation>,
+    pub pending_copy: Option<PendingCopy>,
+    pub copy_job: Option<CopyJob>,
+    pub pending_db_rename: Option<PendingDatabaseRename>,
+    pub rename_job: Option<RenameJob>,
+    pub truncate_job: Option<TruncateJob>,
+    pub pending_kill_op: Option<PendingKillOp>,
+    pub pending_warned_write: Option<WarnedWrite>,
+    pub dashboard: Option<Dashboard>,
+    pub info_panel: Option<InfoPanel>,
+    pub form: Option<FormState>,
+    /// Buffered writes while staging is on; `None` when changes apply directly.
+    pub staged_changes: Option<Vec<StagedChange>>,
+    /// Connection the staged changes belong to; they only commit through it.
+    pub staging_uri: Option<String>,
+    pub reviewing_staged: bool,
+    pub show_tree_hints: bool,
+    /// Document count and data size per collection, shown next to tree entries.
+    pub collection_hints: HashMap<(String, String, String), (i64, i64)>,
+    /// Views among the listed collections, keyed by (uri, db, name).
+    pub views: HashMap<(String, String, String), ViewInfo>,
 }
 
 impl Default for AppState {
@@ -241,6 +520,24 @@ impl Default for AppState {
             pending_deletion: None,
             pending_bulk_update: None,
             pending_bulk_deletion: None,
+            pending_clone: None,
+            pending_validation: None,
+            pending_copy: None,
+            copy_job: None,
+            pending_db_rename: None,
+            rename_job: None,
+            truncate_job: None,
+            pending_kill_op: None,
+            pending_warned_write: None,
+            dashboard: None,
+            info_panel: None,
+            form: None,
+            staged_changes: None,
+            staging_uri: None,
+            reviewing_staged: false,
+            show_tree_hints: false,
+            collection_hints: HashMap::new(),
+            views: HashMap::new(),
         }
     }
 }
@@ -362,6 +659,134 @@ impl AppState {
             }
         }
     }
+    /// Shows import violations. CSV/TSV `files` still to be mapped open once
+    /// the report is closed, so they are not dropped along with the failures.
+    pub fn show_import_report(
+        &mut self,
+        title: &str,
+        violations: Vec<String>,
+        uri: &str,
+        db: &str,
+        files: Vec<PathBuf>,
+    ) {
+        self.show_violations(title, violations);
+        if files.is_empty() {
+            return;
+        }
+        if let Some(panel) = &mut self.info_panel {
+            panel.title = format!("{title} · Esc: map {} CSV/TSV file(s)", files.len());
+            panel.kind = PanelKind::ImportReport {
+                uri: uri.to_string(),
+                db: db.to_string(),
+                files,
+            };
+        }
+    }
+
+    pub fn show_violations(&mut self, title: &str, violations: Vec<String>) {
+        let lines = violations
+            .into_iter()
+            .map(|violation| PanelLine::new(PanelTone::Removed, violation))
+            .collect();
+        self.popup_message = None;
+        self.info_panel = Some(InfoPanel::new(title, lines));
+    }
+
+    /// Asks whether to send a write the validator only warns about; call it
+    /// after the violations are shown.
+    pub fn confirm_warned_write(&mut self, write: WarnedWrite) {
+        self.popup_message = Some(write.confirmation_message());
+        self.pending_warned_write = Some(write);
+    }
+
+    /// Buffers a change and mirrors it in the loaded documents so the view
+    /// shows the staged state until it is committed or discarded.
+    pub fn stage(&mut self, change: StagedChange) {
+        if let Some(message) = self.staging_connection_mismatch() {
+            self.popup_message = Some(message);
+            return;
+        }
+        let Some(changes) = &mut self.staged_changes else {
+            return;
+        };
+        let summary = change.summary();
+
+        match &change {
+            StagedChange::Update { edited, .. } => {
+                if let Some(slot) = self
+                    .current_documents
+                    .iter_mut()
+                    .find(|doc| doc.get("_id") == edited.get("_id"))
+                {
+                    *slot = edited.clone();
+                }
+            }
+            StagedChange::Delete { document, .. } => {
+                self.current_documents
+                    .retain(|doc| doc.get("_id") != document.get("_id"));
+                self.selected_doc_index = self
+                    .selected_doc_index
+                    .min(self.current_documents.len().saturating_sub(1));
+                self.selected_field_index = 0;
+                self.expanded_field = None;
+            }
+            StagedChange::Insert { .. } => {}
+        }
+
+        stage_change(changes, change);
+        self.popup_message = None;
+        self.popup_message_success = Some(format!(
+            "📝 Staged {summary} · {} pending (B: review)",
+            changes.len()
+        ));
+    }
+
+    /// The prompt of whichever confirmation is waiting for y/n, if any.
+    pub fn pending_confirmation_message(&self) -> Option<String> {
+        if let Some(deletion) = &self.pending_deletion {
+            return Some(deletion.confirmation_message());
+        }
+        if let Some(update) = &self.pending_bulk_update {
+            return Some(update.confirmation_message());
+        }
+        if let Some(kill) = &self.pending_kill_op {
+            return Some(kill.confirmation_message());
+        }
+        if let Some(rename) = &self.pending_db_rename {
+            return Some(rename.confirmation_message());
+        }
+        if let Some(validation) = &self.pending_validation {
+            return Some(validation.confirmation_message());
+        }
+        if let Some(write) = &self.pending_warned_write {
+            return Some(write.confirmation_message());
+        }
+        if self.reviewing_staged {
+            return self.staged_confirmation_message();
+        }
+        None
+    }
+
+    /// Staged changes must be written through the connection they were
+    /// staged on, not whichever one is current.
+    pub fn staging_connection_mismatch(&self) -> Option<String> {
+        let staging_uri = self.staging_uri.as_ref()?;
+        if self.connected_uri.as_ref() == Some(staging_uri) {
+            return None;
+        }
+        Some(format!(
+            "❗ Staged changes belong to {staging_uri}; reconnect to it to commit them."
+        ))
+    }
+
+    pub fn staged_confirmation_message(&self) -> Option<String> {
+        let changes = self.staged_changes.as_ref()?;
+        Some(format!(
+            "Commit {} staged change(s) in one transaction? [y/N, x: discard all]",
+            changes.len()
+        ))
+    }
+
     pub fn update_graphemes(&mut self) {
         self.input_graphemes = self
             .input_text
@@ -4,7 +4,7 @@ use mongodb::{
     bson::oid::ObjectId,
     bson::{Bson, Document, doc},
     error::Error,
-    options::{ClientOptions, FindOptions},
+    options::{ClientOptions, CreateCollectionOptions, FindOptions, UpdateModifications},
 };
 use serde_json::from_str;
 use std::time::Duration;
@@ -128,7 +128,7 @@ pub async fn update_documents_with_filter(
     db_name: &str,
     collection_name: &str,
     filter: Document,
-    update: Document,
+    update: UpdateModifications,
 ) -> Result<u64, mongodb::error::Error> {
     client
         .database(db_name)
@@ -160,6 +160,22 @@ pub async fn apply_edited_document(
 ) -> Result<(), Box<dyn std::error::Error>> {
     let id_bson = original.get("_id").ok_or("_id field is missing")?.clone();
 
+    let update = edit_update(original, edited);
+    if update.is_empty() {
+        return Ok(());
+    }
+
+    let db = client.database(db_name);
+    let coll = db.collection::<Document>(collection_name);
+    coll.update_one(doc! { "_id": id_bson }, update)
+        .await
+        .map(|_| ())
+        .map_err(Into::into)
+}
+
+/// Builds the `$set`/`$unset` update that turns `original` into `edited`.
+/// Returns an empty document when nothing changed.
+pub fn edit_update(original: &Document, edited: &Document) -> Document {
     let (mut set_doc, unset_doc) = diff_docs_deep(original, edited);
 
     set_doc.remove("_id");
@@ -171,17 +187,7 @@ pub async fn apply_edited_document(
     if !unset_doc.is_empty() {
         update.insert("$unset", Bson::Document(unset_doc));
     }
-
-    if update.is_empty() {
-        return Ok(());
-    }
-
-    let db = client.database(db_name);
-    let coll = db.collection::<Document>(collection_name);
-    coll.update_one(doc! { "_id": id_bson }, update)
-        .await
-        .map(|_| ())
-        .map_err(Into::into)
+    update
 }
 
 fn diff_docs_deep(original: &Document, edited: &Document) -> (Document, Document) {
@@ -268,6 +274,19 @@ pub async fn apply_edited_json(
     };
     apply_edited_document(client, db_name, collection_name, original, &edited_doc).await
 }
+pub async fn create_collection(
+    client: &Client,
+    db_name: &str,
+    collection_name: &str,
+    options: CreateCollectionOptions,
+) -> Result<(), mongodb::error::Error> {
+    client
+        .database(db_name)
+        .create_collection(collection_name)
+        .with_options(options)
+        .await
+}
+
 pub async fn delete_collection(
     client: &Client,
     db_name: &str,
@@ -293,6 +312,37 @@ pub async fn delete_field_in_document(
     collection.update_one(filter, update).await.map(|_| ())
 }
 
+pub async fn set_field_in_document(
+    client: &Client,
+    db_name: &str,
+    collection_name: &str,
+    document_id: Bson,
+    field_name: &str,
+    value: Bson,
+) -> Result<(), mongodb::error::Error> {
+    let db = client.database(db_name);
+    let collection = db.collection::<Document>(collection_name);
+
+    let filter = doc! { "_id": document_id };
+    let update = doc! { "$set": { field_name: value } };
+
+    collection.update_one(filter, update).await.map(|_| ())
+}
+
+pub async fn insert_document(
+    client: &Client,
+    db_name: &str,
+    collection_name: &str,
+    document: Document,
+) -> Result<Bson, mongodb::error::Error> {
+    let db = client.database(db_name);
+    let collection = db.collection::<Document>(collection_name);
+    collection
+        .insert_one(document)
+        .await
+        .map(|result| result.inserted_id)
+}
+
 pub async fn delete_document_by_id(
     client: &Client,
     db_name: &str,
@@ -0,0 +1,239 @@
+use super::indexes::{ID_INDEX, create_index, list_index_details};
+use futures::stream::TryStreamExt;
+use mongodb::{
+    Client, Collection, Cursor, Database,
+    bson::{Document, doc},
+    error::{Error, ErrorKind},
+};
+use std::collections::VecDeque;
+
+/// Documents read and written per step, so the UI can redraw in between.
+pub const COPY_BATCH_SIZE: usize = 500;
+
+/// `IndexOptionsConflict` and `IndexKeySpecsConflict`: the target already has
+/// a clashing index. When merging, the source index is skipped and reported.
+const INDEX_CONFLICT_CODES: [i32; 2] = [85, 86];
+
+const DUPLICATE_KEY_CODE: i32 = 11000;
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum CopyMode {
+    /// Drop each target collection before copying into it.
+    Drop,
+    /// Keep target documents and replace those with the same `_id`.
+    Merge,
+}
+
+pub struct CopyOptions {
+    /// Only documents matching this filter are copied.
+    pub filter: Document,
+    pub copy_indexes: bool,
+    pub mode: CopyMode,
+}
+
+/// A copy of one or more collections that runs a batch at a time from the
+/// main loop.
+pub struct CopyJob {
+    source: Client,
+    target: Client,
+    pub source_db: String,
+    pub target_uri: String,
+    pub target_db: String,
+    /// `(source, target)` collection names that have not been started yet.
+    pending: VecDeque<(String, String)>,
+    current: Option<(Collection<Document>, Cursor<Document>)>,
+    options: CopyOptions,
+    pub total: u64,
+    pub copied: u64,
+    pub collections: usize,
+    /// `collection.index` names left out because they conflict with a target index.
+    pub skipped_indexes: Vec<String>,
+}
+
+impl CopyJob {
+    pub async fn new(
+        source: Client,
+        source_db: &str,
+        target: Client,
+        target_uri: &str,
+        target_db: &str,
+        collections: Vec<(String, String)>,
+        options: CopyOptions,
+    ) -> Result<Self, mongodb::error::Error> {
+        let mut total = 0;
+        for (collection, _) in &collections {
+            total += source
+                .database(source_db)
+                .collection::<Document>(collection)
+                .count_documents(options.filter.clone())
+                .await?;
+        }
+
+        Ok(Self {
+            source,
+            target,
+            source_db: source_db.to_string(),
+            target_uri: target_uri.to_string(),
+            target_db: target_db.to_string(),
+            collections: collections.len(),
+            pending: collections.into(),
+            current: None,
+            options,
+            total,
+            copied: 0,
+            skipped_indexes: Vec::new(),
+        })
+    }
+
+    pub fn collections_done(&self) -> usize {
+        self.collections - self.pending.len() - usize::from(self.current.is_some())
+    }
+
+    async fn start_collection(
+        &mut self,
+        source_name: &str,
+        target_name: &str,
+    ) -> Result<(), mongodb::error::Error> {
+        let source = self
+            .source
+            .database(&self.source_db)
+            .collection::<Document>(source_name);
+        let target = self
+            .target
+            .database(&self.target_db)
+            .collection::<Document>(target_name);
+
+        if self.options.mode == CopyMode::Drop {
+            target.drop().await?;
+        }
+        if self.options.copy_indexes {
+            for index in list_index_details(&self.source, &self.source_db, source_name).await? {
+                if index.name == ID_INDEX {
+                    continue;
+                }
+                let mut spec = index.spec;
+                spec.remove("v");
+                spec.remove("ns");
+                match create_index(&self.target, &self.target_db, target_name, spec).await {
+                    Err(error)
+                        if self.options.mode == CopyMode::Merge
+                            && matches!(
+                                &*error.kind,
+                                ErrorKind::Command(command)
+                                    if INDEX_CONFLICT_CODES.contains(&command.code)
+                            ) =>
+                    {
+                        self.skipped_indexes
+                            .push(format!("{target_name}.{}", index.name));
+                    }
+                    result => {
+                        result?;
+                    }
+                }
+            }
+        }
+
+        let cursor = source
+            .find(self.options.filter.clone())
+            .batch_size(COPY_BATCH_SIZE as u32)
+            .await?;
+        self.current = Some((target, cursor));
+        Ok(())
+    }
+
+    /// Copies the next batch. Returns `false` once every collection is done.
+    pub async fn step(&mut self) -> Result<bool, mongodb::error::Error> {
+        if self.current.is_none() {
+            let Some((source, target)) = self.pending.pop_front() else {
+                return Ok(false);
+            };
+            self.start_collection(&source, &target).await?;
+        }
+        let Some((target, cursor)) = &mut self.current else {
+            return Ok(false);
+        };
+
+        let mut batch = Vec::with_capacity(COPY_BATCH_SIZE);
+        while batch.len() < COPY_BATCH_SIZE {
+            match cursor.try_next().await? {
+                Some(document) => batch.push(document),
+                None => break,
+            }
+        }
+        let exhausted = batch.len() < COPY_BATCH_SIZE;
+        let written = batch.len() as u64;
+
+        match self.options.mode {
+            CopyMode::Drop if !batch.is_empty() => {
+                target.insert_many(batch).await?;
+            }
+            CopyMode::Drop => {}
+            CopyMode::Merge if !batch.is_empty() => {
+                let database = self.target.database(&self.target_db);
+                merge_batch(&database, target, batch).await?;
+            }
+            CopyMode::Merge => {}
+        }
+
+        self.copied += written;
+        if exhausted {
+            self.current = None;
+        }
+        Ok(self.current.is_some() || !self.pending.is_empty())
+    }
+}
+
+/// Inserts `batch` unordered, then replaces the documents whose `_id` already
+/// existed at the target with a single `update` command.
+async fn merge_batch(
+    database: &Database,
+    target: &Collection<Document>,
+    batch: Vec<Document>,
+) -> Result<(), Error> {
+    let error = match target.insert_many(&batch).ordered(false).await {
+        Ok(_) => return Ok(()),
+        Err(error) => error,
+    };
+    let ErrorKind::InsertMany(failure) = &*error.kind else {
+        return Err(error);
+    };
+    let Some(write_errors) = &failure.write_errors else {
+        return Err(error);
+    };
+    let mut updates = Vec::with_capacity(write_errors.len());
+    for write_error in write_errors {
+        let id = batch
+            .get(write_error.index)
+            .and_then(|document| document.get("_id"));
+        match id {
+            Some(id) if write_error.code == DUPLICATE_KEY_CODE => updates.push(doc! {
+                "q": { "_id": id.clone() },
+                "u": batch[write_error.index].clone(),
+                "upsert": true,
+            }),
+            _ => return Err(error),
+        }
+    }
+    if failure.write_concern_error.is_some() {
+        return Err(error);
+    }
+
+    let reply = database
+        .run_command(doc! {
+            "update": target.name(),
+            "updates": updates,
+            "ordered": false,
+        })
+        .await?;
+    match reply.get_array("writeErrors") {
+        // `Error::custom` would only display as "Custom user error".
+        Ok(errors) if !errors.is_empty() => Err(std::io::Error::other(format!(
+            "{} document(s) could not be replaced in {}, first: {}",
+            errors.len(),
+            target.name(),
+            errors[0]
+        ))
+        .into()),
+        _ => Ok(()),
+    }
+}
@@ -0,0 +1,416 @@
+use mongodb::bson::{Bson, DateTime, Document, oid::ObjectId};
+use std::{
+    collections::HashMap,
+    io::{self, Write},
+};
+
+/// Separator placed between elements when arrays are joined into one cell.
+pub const ARRAY_SEPARATOR: &str = ";";
+
+/// Rows looked at when guessing a column's type on import.
+pub const INFERENCE_SAMPLE: usize = 100;
+
+/// Labels offered on the import column-mapping screen, in `ColumnType` order.
+pub const COLUMN_TYPES: [&str; 7] = [
+    "string", "int", "double", "bool", "date", "objectId", "skip",
+];
+
+/// How an imported column is converted. `Skip` leaves the column out.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ColumnType {
+    String,
+    Int,
+    Double,
+    Bool,
+    Date,
+    ObjectId,
+    Skip,
+}
+
+impl ColumnType {
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::String => "string",
+            Self::Int => "int",
+            Self::Double => "double",
+            Self::Bool => "bool",
+            Self::Date => "date",
+            Self::ObjectId => "objectId",
+            Self::Skip => "skip",
+        }
+    }
+
+    pub fn from_label(label: &str) -> Self {
+        match label {
+            "int" => Self::Int,
+            "double" => Self::Double,
+            "bool" => Self::Bool,
+            "date" => Self::Date,
+            "objectId" => Self::ObjectId,
+            "skip" => Self::Skip,
+            _ => Self::String,
+        }
+    }
+
+    /// Converts one cell. Empty cells give `None` so the field is left out,
+    /// except for strings where the empty string is kept.
+    pub fn convert(self, cell: &str) -> Result<Option<Bson>, String> {
+        let text = cell.trim();
+        if self == Self::Skip || (text.is_empty() && self != Self::String) {
+            return Ok(None);
+        }
+        let value = match self {
+            Self::Int => text
+                .parse::<i64>()
+                .map(|number| match i32::try_from(number) {
+                    Ok(small) => Bson::Int32(small),
+                    Err(_) => Bson::Int64(number),
+                })
+                .map_err(|_| format!("'{text}' is not an integer"))?,
+            Self::Double => text
+                .parse::<f64>()
+                .map(Bson::Double)
+                .map_err(|_| format!("'{text}' is not a number"))?,
+            Self::Bool => match text.to_ascii_lowercase().as_str() {
+                "true" => Bson::Boolean(true),
+                "false" => Bson::Boolean(false),
+                _ => return Err(format!("'{text}' is not true or false")),
+            },
+            Self::Date => parse_date(text)
+                .map(Bson::DateTime)
+                .ok_or_else(|| format!("'{text}' is not an ISO 8601 date"))?,
+            Self::ObjectId => ObjectId::parse_str(text)
+                .map(Bson::ObjectId)
+                .map_err(|_| format!("'{text}' is not an ObjectId"))?,
+            Self::String | Self::Skip => Bson::String(cell.to_string()),
+        };
+        Ok(Some(value))
+    }
+}
+
+/// Accepts full RFC 3339 timestamps and plain `YYYY-MM-DD` dates (UTC midnight).
+fn parse_date(text: &str) -> Option<DateTime> {
+    DateTime::parse_rfc3339_str(text)
+        .or_else(|_| DateTime::parse_rfc3339_str(format!("{text}T00:00:00Z")))
+        .ok()
+}
+
+/// Picks the narrowest type every non-empty sample converts to. Numbers with
+/// a leading zero or `+`, like zip codes and phone numbers, stay strings, as
+/// do integers too large for a 64-bit int, which a double would round.
+pub fn infer_type<'a>(samples: impl IntoIterator<Item = &'a str> + Clone) -> ColumnType {
+    let values = || {
+        samples
+            .clone()
+            .into_iter()
+            .map(str::trim)
+            .filter(|text| !text.is_empty())
+    };
+    if values().next().is_none() {
+        return ColumnType::String;
+    }
+    let keeps_text = |text: &str| {
+        let digits = text.strip_prefix('-').unwrap_or(text);
+        text.starts_with('+')
+            || (digits.starts_with('0') && digits[1..].starts_with(|c: char| c.is_ascii_digit()))
+            || (digits.chars().all(|c| c.is_ascii_digit()) && text.parse::<i64>().is_err())
+    };
+    let textual = values().any(keeps_text);
+    [
+        ColumnType::Int,
+        ColumnType::Double,
+        ColumnType::Bool,
+        ColumnType::ObjectId,
+        ColumnType::Date,
+    ]
+    .into_iter()
+    .filter(|kind| !(textual && matches!(kind, ColumnType::Int | ColumnType::Double)))
+    .find(|kind| values().all(|text| kind.convert(text).is_ok()))
+    .unwrap_or(ColumnType::String)
+}
+
+/// Splits delimited text into rows, honouring double-quoted cells with
+/// embedded delimiters, doubled quotes and line breaks. Tab-separated text has
+/// no quoting, so quotes there are kept as written. Blank lines are dropped.
+pub fn parse_delimited(text: &str, delimiter: char) -> Vec<Vec<String>> {
+    let quoting = delimiter != '\t';
+    let mut rows = Vec::new();
+    let mut row = Vec::new();
+    let mut cell = String::new();
+    let mut quoted = false;
+    let mut chars = text.trim_start_matches('\u{feff}').chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if quoted && chars.peek() == Some(&'"') => {
+                cell.push('"');
+                chars.next();
+            }
+            '"' if quoted => quoted = false,
+            '"' if quoting && cell.is_empty() => quoted = true,
+            '\r' if !quoted => {}
+            '\n' if !quoted => {
+                row.push(std::mem::take(&mut cell));
+                if row.len() > 1 || !row[0].is_empty() {
+                    rows.push(std::mem::take(&mut row));
+                } else {
+                    row.clear();
+                }
+            }
+            c if c == delimiter && !quoted => row.push(std::mem::take(&mut cell)),
+            c => cell.push(c),
+        }
+    }
+    row.push(cell);
+    if row.len() > 1 || !row[0].is_empty() {
+        rows.push(row);
+    }
+    rows
+}
+
+/// Builds a document from one row. Dotted headers such as `address.city`
+/// become nested documents; cells converting to `None` are left out. A row
+/// must have exactly one cell per header.
+pub fn build_document(
+    headers: &[String],
+    types: &[ColumnType],
+    row: &[String],
+) -> Result<Document, String> {
+    if row.len() != headers.len() {
+        return Err(format!(
+            "expected {} cells, found {}",
+            headers.len(),
+            row.len()
+        ));
+    }
+    let mut document = Document::new();
+    for ((header, kind), cell) in headers.iter().zip(types).zip(row) {
+        let Some(value) = kind
+            .convert(cell)
+            .map_err(|error| format!("{header}: {error}"))?
+        else {
+            continue;
+        };
+        let mut target = &mut document;
+        let mut parts: Vec<&str> = header.split('.').collect();
+        let leaf = parts.pop().unwrap_or_default();
+        for part in parts {
+            let entry = target
+                .entry(part.to_string())
+                .or_insert_with(|| Bson::Document(Document::new()));
+            let Bson::Document(inner) = entry else {
+                return Err(format!("{header}: '{part}' is already a value"));
+            };
+            target = inner;
+        }
+        target.insert(leaf, value);
+    }
+    Ok(document)
+}
+
+/// How array values become CSV cells.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum ArrayMode {
+    /// One cell with the elements joined by `ARRAY_SEPARATOR`.
+    Join,
+    /// One column per element: `tags.0`, `tags.1`, ...
+    Index,
+}
+
+/// Flattens `doc` into `(dotted path, cell)` pairs in field order.
+pub fn flatten(doc: &Document, arrays: ArrayMode) -> Vec<(String, String)> {
+    let mut cells = Vec::new();
+    for (key, value) in doc {
+        flatten_value(key, value, arrays, &mut cells);
+    }
+    cells
+}
+
+fn flatten_value(path: &str, value: &Bson, arrays: ArrayMode, cells: &mut Vec<(String, String)>) {
+    match value {
+        Bson::Document(inner) if !inner.is_empty() => {
+            for (key, value) in inner {
+                flatten_value(&format!("{path}.{key}"), value, arrays, cells);
+            }
+        }
+        Bson::Array(items) if arrays == ArrayMode::Index && !items.is_empty() => {
+            for (index, value) in items.iter().enumerate() {
+                flatten_value(&format!("{path}.{index}"), value, arrays, cells);
+            }
+        }
+        Bson::Array(items) => {
+            let joined: Vec<String> = items.iter().map(cell_text).collect();
+            cells.push((path.to_string(), joined.join(ARRAY_SEPARATOR)));
+        }
+        _ => cells.push((path.to_string(), cell_text(value))),
+    }
+}
+
+/// Plain text for a single value. Types without an obvious spelling fall
+/// back to relaxed extended JSON.
+pub fn cell_text(value: &Bson) -> String {
+    match value {
+        Bson::String(text) => text.clone(),
+        Bson::ObjectId(id) => id.to_hex(),
+        Bson::DateTime(date) => date
+            .try_to_rfc3339_string()
+            .unwrap_or_else(|_| date.timestamp_millis().to_string()),
+        Bson::Int32(number) => number.to_string(),
+        Bson::Int64(number) => number.to_string(),
+        Bson::Double(number) => number.to_string(),
+        Bson::Decimal128(number) => number.to_string(),
+        Bson::Boolean(flag) => flag.to_string(),
+        Bson::Null | Bson::Undefined => String::new(),
+        other => other.clone().into_relaxed_extjson().to_string(),
+    }
+}
+
+/// One cell per requested field, in the order given. A field that is not a
+/// flattened column, such as `address` or `tags.0` with joined arrays, gets
+/// the value at that path as a single cell.
+pub fn field_cells(doc: &Document, fields: &[String], arrays: ArrayMode) -> Vec<String> {
+    let cells: HashMap<String, String> = flatten(doc, arrays).into_iter().collect();
+    fields
+        .iter()
+        .map(|field| match cells.get(field) {
+            Some(cell) => cell.clone(),
+            None => value_at(doc, field).map(cell_text).unwrap_or_default(),
+        })
+        .collect()
+}
+
+fn value_at<'a>(doc: &'a Document, path: &str) -> Option<&'a Bson> {
+    let mut parts = path.split('.');
+    let mut value = doc.get(parts.next()?)?;
+    for part in parts {
+        value = match value {
+            Bson::Document(inner) => inner.get(part)?,
+            Bson::Array(items) => items.get(part.parse::<usize>().ok()?)?,
+            _ => return None,
+        };
+    }
+    Some(value)
+}
+
+/// Writes one row, quoting cells that contain the delimiter, quotes or line breaks.
+pub fn write_row(out: &mut impl Write, cells: &[String], delimiter: char) -> io::Result<()> {
+    let quoted: Vec<String> = cells
+        .iter()
+        .map(|cell| {
+            if cell.contains(delimiter) || cell.contains(['"', '\n', '\r']) {
+                format!("\"{}\"", cell.replace('"', "\"\""))
+            } else {
+                cell.clone()
+            }
+        })
+        .collect();
+    writeln!(out, "{}", quoted.join(&delimiter.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mongodb::bson::doc;
+
+    #[test]
+    fn imports_typed_rows_into_nested_documents() {
+        let rows = parse_delimited(
+            "name,address.city,age,joined\r\n\"Lovelace, Ada\",London,36,1815-12-10\n\nBabbage,,79,\n",
+            ',',
+        );
+        assert_eq!(rows.len(), 3);
+        assert_eq!(rows[1][0], "Lovelace, Ada");
+
+        let headers = rows[0].clone();
+        let types: Vec<ColumnType> = (0..headers.len())
+            .map(|column| infer_type(rows[1..].iter().map(|row| row[column].as_str())))
+            .collect();
+        assert_eq!(
+            types,
+            vec![
+                ColumnType::String,
+                ColumnType::String,
+                ColumnType::Int,
+                ColumnType::Date
+            ]
+        );
+
+        assert_eq!(infer_type(["01234", "90210"]), ColumnType::String);
+        assert_eq!(infer_type(["+44 20 7946 0958"]), ColumnType::String);
+        assert_eq!(infer_type(["0", "-3", "0.5"]), ColumnType::Double);
+        assert_eq!(
+            infer_type(["1", "92233720368547758070"]),
+            ColumnType::String
+        );
+        assert_eq!(
+            parse_delimited("\"a\t5\" x\tb\n", '\t'),
+            vec![vec!["\"a", "5\" x", "b"]]
+        );
+
+        let document = build_document(&headers, &types, &rows[1]).unwrap();
+        assert_eq!(
+            document.get_document("address").unwrap().get_str("city"),
+            Ok("London")
+        );
+        assert_eq!(document.get_i32("age"), Ok(36));
+        assert!(document.get_datetime("joined").is_ok());
+
+        let document = build_document(&headers, &types, &rows[2]).unwrap();
+        assert!(!document.contains_key("joined"));
+        assert!(build_document(&headers, &[ColumnType::Int; 4], &rows[1]).is_err());
+        assert_eq!(
+            build_document(&headers, &types, &rows[1][..3]),
+            Err("expected 4 cells, found 3".to_string())
+        );
+    }
+
+    #[test]
+    fn flattens_nested_documents_and_arrays() {
+        let document = doc! {
+            "name": "Ada, Countess",
+            "address": { "city": "London", "zip": 1815 },
+            "tags": ["math", "poetry"],
+            "note": Bson::Null,
+        };
+
+        let joined = flatten(&document, ArrayMode::Join);
+        assert_eq!(
+            joined,
+            vec![
+                ("name".to_string(), "Ada, Countess".to_string()),
+                ("address.city".to_string(), "London".to_string()),
+                ("address.zip".to_string(), "1815".to_string()),
+                ("tags".to_string(), "math;poetry".to_string()),
+                ("note".to_string(), String::new()),
+            ]
+        );
+
+        let indexed: Vec<String> = flatten(&document, ArrayMode::Index)
+            .into_iter()
+            .map(|(path, _)| path)
+            .collect();
+        assert!(indexed.contains(&"tags.1".to_string()));
+
+        let fields = [
+            "tags.1".to_string(),
+            "address".to_string(),
+            "age".to_string(),
+        ];
+        assert_eq!(
+            field_cells(&document, &fields, ArrayMode::Join),
+            vec!["poetry", r#"{"city":"London","zip":1815}"#, ""]
+        );
+
+        let mut row = Vec::new();
+        write_row(
+            &mut row,
+            &["Ada, Countess".to_string(), "say \"hi\"".to_string()],
+            ',',
+        )
+        .unwrap();
+        assert_eq!(
+            String::from_utf8(row).unwrap(),
+            "\"Ada, Countess\",\"say \"\"hi\"\"\"\n"
+        );
+    }
+}
@@ -59,6 +59,27 @@ pub async fn handle_connection(state: &mut AppState, uri: &str) {
 
     state.db_to_expand = None;
 }
+/// Fetches the count and size hints shown next to each collection of a database.
+pub async fn load_collection_hints(state: &mut AppState, db_uri: &str, db_name: &str) {
+    let Some(client) = state.mongo_client.clone() else {
+        return;
+    };
+    let collections = state
+        .collection_map
+        .get(&(db_uri.to_string(), db_name.to_string()))
+        .cloned()
+        .unwrap_or_default();
+
+    for collection in collections {
+        if let Ok(stats) = db::stats::collection_stats(&client, db_name, &collection).await {
+            state.collection_hints.insert(
+                (db_uri.to_string(), db_name.to_string(), collection),
+                (stats.count, stats.size),
+            );
+        }
+    }
+}
+
 pub async fn handle_collection_listing(state: &mut AppState, db_uri: &str, db_name: &str) {
     if let Some(client) = &state.mongo_client {
         match db::client::list_collections(client, db_name).await {
@@ -69,7 +90,18 @@ pub async fn handle_collection_listing(state: &mut AppState, db_uri: &str, db_na
                 state
                     .expanded_dbs
                     .insert((db_uri.to_string(), db_name.to_string()));
+                if let Ok(views) = db::views::list_views(client, db_name).await {
+                    state
+                        .views
+                        .retain(|(uri, db, _), _| uri != db_uri || db != db_name);
+                    state.views.extend(views.into_iter().map(|(name, view)| {
+                        ((db_uri.to_string(), db_name.to_string(), name), view)
+                    }));
+                }
                 state.rebuild_tree_items();
+                if state.show_tree_hints {
+                    load_collection_hints(state, db_uri, db_name).await;
+                }
             }
             Err(e) => {
                 state.popup_message = Some(format!(
@@ -136,3 +168,123 @@ pub async fn fetch_and_update_documents(state: &mut AppState, uri: &str, db: &st
 
     state.fetch_collection_data = None;
 }
+
+/// Runs one batch of the active copy and reports the outcome once it ends.
+pub async fn advance_copy_job(state: &mut AppState) {
+    let Some(job) = &mut state.copy_job else {
+        return;
+    };
+
+    match job.step().await {
+        Ok(true) => {}
+        Ok(false) => {
+            let (uri, db) = (job.target_uri.clone(), job.target_db.clone());
+            state.popup_message_success = Some(format!(
+                "✅ Copied {} document(s) from {} collection(s) into {db}",
+                job.copied, job.collections
+            ));
+            if !job.skipped_indexes.is_empty() {
+                state.popup_message = Some(format!(
+                    "⚠️ Skipped {} index(es) that conflict with existing target indexes: {}",
+                    job.skipped_indexes.len(),
+                    job.skipped_indexes.join(", ")
+                ));
+            }
+            state.copy_job = None;
+            if state.connected_uri.as_deref() == Some(uri.as_str()) {
+                let databases = state.database_map.entry(uri.clone()).or_default();
+                if !databases.contains(&db) {
+                    databases.push(db.clone());
+                }
+                state.expanded_uris.insert(uri.clone());
+                state.collection_to_load = Some((uri, db));
+                state.rebuild_tree_items();
+            }
+        }
+        Err(e) => {
+            state.popup_message = Some(format!(
+                "❌ Copy stopped after {} document(s): {}",
+                job.copied, e
+            ));
+            state.copy_job = None;
+        }
+    }
+}
+
+/// Runs one step of the active database rename, rolling back if it fails.
+pub async fn advance_rename_job(state: &mut AppState) {
+    let Some(job) = &mut state.rename_job else {
+        return;
+    };
+
+    match job.step().await {
+        Ok(true) => {}
+        Ok(false) => {
+            let (uri, source, target) = (
+                job.uri.clone(),
+                job.source_db.clone(),
+                job.target_db.clone(),
+            );
+            state.rename_job = None;
+            if let Some(databases) = state.database_map.get_mut(&uri) {
+                databases.retain(|db| *db != source);
+                databases.push(target.clone());
+                databases.sort();
+            }
+            state.expanded_dbs.remove(&(uri.clone(), source.clone()));
+            state.collection_map.remove(&(uri.clone(), source.clone()));
+            if state
+                .selected_collection
+                .as_ref()
+                .is_some_and(|(_, db, _)| *db == source)
+            {
+                state.selected_collection = None;
+                state.current_documents.clear();
+            }
+            state.collection_to_load = Some((uri, target.clone()));
+            state.rebuild_tree_items();
+            state.popup_message_success =
+                Some(format!("✅ Renamed database '{source}' to '{target}'"));
+        }
+        Err(e) => {
+            let rollback = job.rollback().await;
+            let (uri, source) = (job.uri.clone(), job.source_db.clone());
+            state.rename_job = None;
+            state.popup_message = Some(match rollback {
+                Ok(()) => format!("❌ Rename failed and was rolled back: {}", e),
+                Err(rollback) => format!(
+                    "❌ Rename failed: {}; rollback also failed: {}",
+                    e, rollback
+                ),
+            });
+            state.collection_to_load = Some((uri, source));
+        }
+    }
+}
+
+/// Deletes one batch for the active truncate and reports when it is done.
+pub async fn advance_truncate_job(state: &mut AppState) {
+    let Some(job) = &mut state.truncate_job else {
+        return;
+    };
+
+    match job.step().await {
+        Ok(true) => {}
+        Ok(false) => {
+            state.popup_message_success = Some(format!(
+                "✅ Emptied {}.{}: deleted {} document(s)",
+                job.db, job.name, job.deleted
+            ));
+            state.truncate_job = None;
+            state.reload_documents_for_selected_collection().await;
+        }
+        Err(e) => {
+            state.popup_message = Some(format!(
+                "❌ Emptying stopped after {} document(s): {}",
+                job.deleted, e
+            ));
+            state.truncate_job = None;
+            state.reload_documents_for_selected_collection().await;
+        }
+    }
+}
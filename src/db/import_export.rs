@@ -1,40 +1,209 @@
+use super::csv::{
+    ArrayMode, ColumnType, build_document, field_cells, flatten, parse_delimited, write_row,
+};
+use super::validation::{check_write, fetch_validator};
 use futures::stream::TryStreamExt;
 use mongodb::{
-    Client,
+    Client, Cursor,
     bson::{Document, doc},
+    options::FindOptions,
 };
 use std::{
+    collections::{HashMap, HashSet},
     ffi::OsStr,
+    fmt,
     fs::File,
-    io::{BufRead, BufReader, Write},
+    io::{BufRead, BufReader, BufWriter, Write},
+    path::Path,
 };
 use tokio::fs::{self};
 
+/// Returned by `import_collection` when rows fail the target collection's
+/// validator; nothing is inserted in that case.
+#[derive(Debug)]
+pub struct PreflightError {
+    pub violations: Vec<String>,
+    /// Set when the validator only warns: the documents, which the server
+    /// would accept, so they can still be inserted with `insert_documents`.
+    pub documents: Option<Vec<Document>>,
+}
+
+impl fmt::Display for PreflightError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} validation error(s), first: {}",
+            self.violations.len(),
+            self.violations.first().map(String::as_str).unwrap_or("")
+        )
+    }
+}
+
+impl std::error::Error for PreflightError {}
+
+/// The documents an export reads. Empty sort and projection are left out.
+/// A `pipeline` replaces the find; `limit` caps either.
+#[derive(Default)]
+pub struct ExportQuery {
+    pub filter: Document,
+    pub sort: Document,
+    pub projection: Document,
+    pub pipeline: Option<Vec<Document>>,
+    pub limit: Option<i64>,
+}
+
+pub enum ExportFormat {
+    /// One relaxed extended JSON document per line, readable by `import_collection`.
+    JsonLines,
+    /// Flattened rows with a header; `fields` picks and orders the columns.
+    Delimited {
+        delimiter: char,
+        fields: Vec<String>,
+        arrays: ArrayMode,
+    },
+}
+
 pub async fn export_collection(
     client: &Client,
     db_name: &str,
     collection_name: &str,
     file_path: &str,
 ) -> Result<(), Box<dyn std::error::Error>> {
+    export_query(
+        client,
+        db_name,
+        collection_name,
+        file_path,
+        &ExportQuery::default(),
+        &ExportFormat::JsonLines,
+    )
+    .await?;
+    Ok(())
+}
+
+/// Writes the documents matching `query` to `file_path` and returns how many
+/// were exported.
+pub async fn export_query(
+    client: &Client,
+    db_name: &str,
+    collection_name: &str,
+    file_path: &str,
+    query: &ExportQuery,
+    format: &ExportFormat,
+) -> Result<u64, Box<dyn std::error::Error>> {
     let db = client.database(db_name);
     let collection = db.collection::<Document>(collection_name);
 
-    let mut cursor = collection.find(doc! {}).await?;
+    let mut cursor = match &query.pipeline {
+        Some(pipeline) => {
+            let mut stages = pipeline.clone();
+            if let Some(limit) = query.limit {
+                stages.push(doc! { "$limit": limit });
+            }
+            collection.aggregate(stages).await?
+        }
+        None => {
+            let options = FindOptions::builder()
+                .sort(Some(query.sort.clone()).filter(|sort| !sort.is_empty()))
+                .projection(
+                    Some(query.projection.clone()).filter(|projection| !projection.is_empty()),
+                )
+                .limit(query.limit)
+                .build();
+            collection
+                .find(query.filter.clone())
+                .with_options(options)
+                .await?
+        }
+    };
 
-    let path = std::path::Path::new(file_path);
+    let path = Path::new(file_path);
 
     if let Some(parent) = path.parent() {
         tokio::fs::create_dir_all(parent).await?;
     }
 
-    let mut file = File::create(path)?;
+    let mut file = BufWriter::new(File::create(path)?);
+    let mut exported = 0;
+
+    match format {
+        ExportFormat::JsonLines => {
+            while let Some(doc) = cursor.try_next().await? {
+                let json_str = serde_json::to_string(&doc)?;
+                writeln!(file, "{}", json_str)?;
+                exported += 1;
+            }
+        }
+        ExportFormat::Delimited {
+            delimiter,
+            fields,
+            arrays,
+        } if !fields.is_empty() => {
+            // The columns are known up front, so rows are written as they arrive.
+            write_row(&mut file, fields, *delimiter)?;
+            while let Some(doc) = cursor.try_next().await? {
+                write_row(&mut file, &field_cells(&doc, fields, *arrays), *delimiter)?;
+                exported += 1;
+            }
+        }
+        ExportFormat::Delimited {
+            delimiter, arrays, ..
+        } => {
+            // Columns are only known once every document has been flattened,
+            // so rows go to a scratch file next to the export, not to memory.
+            let spill_path = path.with_extension("rows.tmp");
+            let written =
+                write_delimited(&mut cursor, &mut file, &spill_path, *delimiter, *arrays).await;
+            if spill_path.exists() {
+                std::fs::remove_file(&spill_path)?;
+            }
+            exported = written?;
+        }
+    }
+
+    file.flush()?;
+    Ok(exported)
+}
 
+/// Writes flattened rows with a header of every column found. Each row is
+/// spilled to `spill_path` while the columns are collected, then read back in
+/// column order.
+async fn write_delimited(
+    cursor: &mut Cursor<Document>,
+    out: &mut impl Write,
+    spill_path: &Path,
+    delimiter: char,
+    arrays: ArrayMode,
+) -> Result<u64, Box<dyn std::error::Error>> {
+    let mut spill = BufWriter::new(File::create(spill_path)?);
+    let mut discovered = Vec::new();
+    let mut seen = HashSet::new();
+    let mut exported = 0;
     while let Some(doc) = cursor.try_next().await? {
-        let json_str = serde_json::to_string(&doc)?;
-        writeln!(file, "{}", json_str)?;
+        let cells = flatten(&doc, arrays);
+        for (column, _) in &cells {
+            if seen.insert(column.clone()) {
+                discovered.push(column.clone());
+            }
+        }
+        writeln!(spill, "{}", serde_json::to_string(&cells)?)?;
+        exported += 1;
     }
+    spill.flush()?;
+    drop(spill);
 
-    Ok(())
+    write_row(out, &discovered, delimiter)?;
+    for line in BufReader::new(File::open(spill_path)?).lines() {
+        let row: HashMap<String, String> = serde_json::from_str::<Vec<(String, String)>>(&line?)?
+            .into_iter()
+            .collect();
+        let cells: Vec<String> = discovered
+            .iter()
+            .map(|column| row.get(column).cloned().unwrap_or_default())
+            .collect();
+        write_row(out, &cells, delimiter)?;
+    }
+    Ok(exported)
 }
 
 pub async fn export_database(
@@ -80,12 +249,137 @@ pub async fn import_collection(
         docs.push(doc);
     }
 
+    preflight(client, db_name, collection_name, &docs, "line").await?;
+
     if !docs.is_empty() {
         collection.insert_many(docs).await?;
     }
 
     Ok(())
 }
+
+/// Checks `docs` against the collection's validator. Violations are reported
+/// as `{unit} {n}`, counting documents from 1.
+async fn preflight(
+    client: &Client,
+    db_name: &str,
+    collection_name: &str,
+    docs: &[Document],
+    unit: &str,
+) -> Result<(), Box<dyn std::error::Error>> {
+    if let Some(validation) = fetch_validator(client, db_name, collection_name).await? {
+        let violations: Vec<String> = docs
+            .iter()
+            .enumerate()
+            .flat_map(|(index, doc)| {
+                check_write(&validation, doc, None)
+                    .violations
+                    .into_iter()
+                    .map(move |violation| format!("{unit} {}: {violation}", index + 1))
+            })
+            .collect();
+        if !violations.is_empty() {
+            let documents = validation.warns_only().then(|| docs.to_vec());
+            return Err(PreflightError {
+                violations,
+                documents,
+            }
+            .into());
+        }
+    }
+    Ok(())
+}
+
+pub async fn insert_documents(
+    client: &Client,
+    db_name: &str,
+    collection_name: &str,
+    docs: Vec<Document>,
+) -> Result<u64, mongodb::error::Error> {
+    let inserted = docs.len() as u64;
+    if !docs.is_empty() {
+        client
+            .database(db_name)
+            .collection::<Document>(collection_name)
+            .insert_many(docs)
+            .await?;
+    }
+    Ok(inserted)
+}
+
+/// A CSV or TSV file split into its header row and data rows.
+pub struct DelimitedFile {
+    pub headers: Vec<String>,
+    pub rows: Vec<Vec<String>>,
+}
+
+/// Reads a CSV or TSV file; headers are trimmed and must be present, unique
+/// and not nested inside one another.
+pub fn read_delimited(
+    file_path: &str,
+    delimiter: char,
+) -> Result<DelimitedFile, Box<dyn std::error::Error>> {
+    let text = std::fs::read_to_string(file_path)?;
+    let mut rows = parse_delimited(&text, delimiter).into_iter();
+    let headers: Vec<String> = rows
+        .next()
+        .ok_or("the file is empty")?
+        .into_iter()
+        .map(|header| header.trim().to_string())
+        .collect();
+    if headers.iter().any(String::is_empty) {
+        return Err("every column needs a header".into());
+    }
+    let mut seen = HashSet::new();
+    if let Some(duplicate) = headers.iter().find(|header| !seen.insert(header.as_str())) {
+        return Err(format!("the header '{duplicate}' appears more than once").into());
+    }
+    // `a` and `a.b` would both have to be the field `a`.
+    if let Some((parent, child)) = headers.iter().find_map(|parent| {
+        headers
+            .iter()
+            .find(|child| {
+                child
+                    .strip_prefix(parent.as_str())
+                    .is_some_and(|rest| rest.starts_with('.'))
+            })
+            .map(|child| (parent, child))
+    }) {
+        return Err(
+            format!("the header '{parent}' clashes with '{child}' nested inside it").into(),
+        );
+    }
+    Ok(DelimitedFile {
+        headers,
+        rows: rows.collect(),
+    })
+}
+
+/// Imports a CSV or TSV file, converting each column with `types`. Nothing is
+/// inserted when a cell fails to convert or a row fails the validator.
+pub async fn import_delimited(
+    client: &Client,
+    db_name: &str,
+    collection_name: &str,
+    file_path: &str,
+    delimiter: char,
+    types: &[ColumnType],
+) -> Result<u64, Box<dyn std::error::Error>> {
+    let DelimitedFile { headers, rows } = read_delimited(file_path, delimiter)?;
+
+    let docs = rows
+        .iter()
+        .enumerate()
+        .map(|(index, row)| {
+            build_document(&headers, types, row)
+                .map_err(|error| format!("row {}: {error}", index + 1))
+        })
+        .collect::<Result<Vec<Document>, String>>()?;
+
+    preflight(client, db_name, collection_name, &docs, "row").await?;
+
+    Ok(insert_documents(client, db_name, collection_name, docs).await?)
+}
 pub async fn import_database(
     client: &Client,
     db_name: &str,
@@ -0,0 +1,358 @@
+use futures::stream::TryStreamExt;
+use mongodb::{
+    Client,
+    bson::{Bson, DateTime, Document, doc},
+};
+
+pub const ID_INDEX: &str = "_id_";
+/// Indexes with at most this many accesses since their stats were reset are
+/// reported as rarely used.
+pub const LOW_ACCESS_OPS: i64 = 10;
+/// Counters restart with the server or the index, so a low count only means
+/// something once the stats cover at least this many days.
+pub const MIN_STATS_DAYS: i64 = 7;
+
+pub struct IndexInfo {
+    pub name: String,
+    pub keys: Document,
+    /// The full index specification as reported by `listIndexes`.
+    pub spec: Document,
+    pub size: Option<i64>,
+}
+
+impl IndexInfo {
+    pub fn hidden(&self) -> bool {
+        self.spec.get_bool("hidden").unwrap_or(false)
+    }
+
+    /// Short labels for the options that are set on the index.
+    pub fn option_labels(&self) -> Vec<String> {
+        let mut labels = Vec::new();
+        if self.spec.get_bool("unique").unwrap_or(false) {
+            labels.push("unique".to_string());
+        }
+        if self.spec.get_bool("sparse").unwrap_or(false) {
+            labels.push("sparse".to_string());
+        }
+        if let Ok(filter) = self.spec.get_document("partialFilterExpression") {
+            labels.push(format!("partial {filter}"));
+        }
+        if let Some(seconds) = self.spec.get("expireAfterSeconds") {
+            labels.push(format!("TTL {seconds}s"));
+        }
+        if let Ok(locale) = self
+            .spec
+            .get_document("collation")
+            .and_then(|collation| collation.get_str("locale"))
+        {
+            labels.push(format!("collation {locale}"));
+        }
+        if self.hidden() {
+            labels.push("hidden".to_string());
+        }
+        labels
+    }
+}
+
+pub struct IndexUsage {
+    pub name: String,
+    pub ops: i64,
+    pub since: Option<DateTime>,
+}
+
+impl IndexUsage {
+    /// Whether the stats window reaches back `MIN_STATS_DAYS` from `now`.
+    pub fn has_mature_stats(&self, now: DateTime) -> bool {
+        const DAY_MILLIS: i64 = 24 * 60 * 60 * 1000;
+        self.since.is_some_and(|since| {
+            now.timestamp_millis() - since.timestamp_millis() >= MIN_STATS_DAYS * DAY_MILLIS
+        })
+    }
+}
+
+/// Finds indexes whose keys are a leading prefix of another index's keys,
+/// returning `(redundant, covering)` name pairs. Unique, partial, sparse and
+/// TTL indexes are skipped because they enforce more than lookup order, and
+/// a partial, sparse or hidden index cannot stand in for another one.
+pub fn redundant_indexes(indexes: &[IndexInfo]) -> Vec<(String, String)> {
+    let is_plain = |index: &IndexInfo| {
+        index.name != ID_INDEX
+            && !index.spec.get_bool("unique").unwrap_or(false)
+            && !index.spec.get_bool("sparse").unwrap_or(false)
+            && !index.spec.contains_key("partialFilterExpression")
+            && !index.spec.contains_key("expireAfterSeconds")
+            && index
+                .keys
+                .values()
+                .all(|direction| !matches!(direction, Bson::String(_)))
+    };
+    let can_cover = |index: &IndexInfo| {
+        !index.hidden()
+            && !index.spec.get_bool("sparse").unwrap_or(false)
+            && !index.spec.contains_key("partialFilterExpression")
+    };
+
+    let mut redundant = Vec::new();
+    for index in indexes.iter().filter(|index| is_plain(index)) {
+        let covering = indexes.iter().find(|other| {
+            other.name != index.name
+                && can_cover(other)
+                && other.keys.len() > index.keys.len()
+                && other.spec.get("collation") == index.spec.get("collation")
+                && index.keys.iter().zip(other.keys.iter()).all(
+                    |((field, direction), (other_field, other_direction))| {
+                        field == other_field && same_direction(direction, other_direction)
+                    },
+                )
+        });
+        if let Some(covering) = covering {
+            redundant.push((index.name.clone(), covering.name.clone()));
+        }
+    }
+    redundant
+}
+
+/// Key directions compare by value, so `1`, `1i64` and `1.0` are the same.
+fn same_direction(direction: &Bson, other: &Bson) -> bool {
+    let number = |value: &Bson| match value {
+        Bson::Int32(number) => Some(f64::from(*number)),
+        Bson::Int64(number) => Some(*number as f64),
+        Bson::Double(number) => Some(*number),
+        _ => None,
+    };
+    match (number(direction), number(other)) {
+        (Some(direction), Some(other)) => direction == other,
+        _ => direction == other,
+    }
+}
+
+/// Reads `$indexStats`, summing accesses across hosts and keeping the
+/// earliest `since` for each index.
+pub async fn index_usage(
+    client: &Client,
+    db_name: &str,
+    collection_name: &str,
+) -> Result<Vec<IndexUsage>, mongodb::error::Error> {
+    let stats: Vec<Document> = client
+        .database(db_name)
+        .collection::<Document>(collection_name)
+        .aggregate(vec![doc! { "$indexStats": {} }])
+        .await?
+        .try_collect()
+        .await?;
+
+    let mut usage: Vec<IndexUsage> = Vec::new();
+    for stat in stats {
+        let name = stat.get_str("name").unwrap_or_default().to_string();
+        let accesses = stat.get_document("accesses").ok();
+        let ops = accesses
+            .and_then(|accesses| accesses.get("ops"))
+            .and_then(|ops| match ops {
+                Bson::Int32(value) => Some(i64::from(*value)),
+                Bson::Int64(value) => Some(*value),
+                _ => None,
+            })
+            .unwrap_or(0);
+        let since = accesses.and_then(|accesses| accesses.get_datetime("since").ok().copied());
+
+        match usage.iter_mut().find(|entry| entry.name == name) {
+            Some(entry) => {
+                entry.ops += ops;
+                entry.since = match (entry.since, since) {
+                    (Some(earliest), Some(since)) => Some(earliest.min(since)),
+                    (earliest, since) => earliest.or(since),
+                };
+            }
+            None => usage.push(IndexUsage { name, ops, since }),
+        }
+    }
+    usage.sort_by_key(|entry| entry.ops);
+    Ok(usage)
+}
+
+/// Builds the name the server would give an index with these keys, e.g.
+/// `{ "a": 1, "b": -1 }` becomes `a_1_b_-1`.
+pub fn default_index_name(keys: &Document) -> String {
+    keys.iter()
+        .map(|(field, direction)| match direction {
+            Bson::String(kind) => format!("{field}_{kind}"),
+            Bson::Double(value) => format!("{field}_{value}"),
+            other => format!("{field}_{other}"),
+        })
+        .collect::<Vec<_>>()
+        .join("_")
+}
+
+pub async fn list_index_details(
+    client: &Client,
+    db_name: &str,
+    collection_name: &str,
+) -> Result<Vec<IndexInfo>, mongodb::error::Error> {
+    let collection = client
+        .database(db_name)
+        .collection::<Document>(collection_name);
+    let models: Vec<_> = collection.list_indexes().await?.try_collect().await?;
+
+    let stats = match collection
+        .aggregate(vec![doc! { "$collStats": { "storageStats": {} } }])
+        .await
+    {
+        Ok(mut cursor) => cursor.try_next().await.ok().flatten(),
+        Err(_) => None,
+    };
+    let sizes = stats
+        .and_then(|stats| stats.get_document("storageStats").ok().cloned())
+        .and_then(|stats| stats.get_document("indexSizes").ok().cloned())
+        .unwrap_or_default();
+
+    Ok(models
+        .into_iter()
+        .map(|model| {
+            let spec = mongodb::bson::to_document(&model).unwrap_or_default();
+            let name = spec
+                .get_str("name")
+                .map(str::to_string)
+                .unwrap_or_else(|_| default_index_name(&model.keys));
+            let size = sizes.get(&name).and_then(|size| match size {
+                Bson::Int32(value) => Some(i64::from(*value)),
+                Bson::Int64(value) => Some(*value),
+                Bson::Double(value) => Some(*value as i64),
+                _ => None,
+            });
+            IndexInfo {
+                name,
+                keys: model.keys,
+                spec,
+                size,
+            }
+        })
+        .collect())
+}
+
+/// Creates an index from a `createIndexes` specification (`key` plus options).
+pub async fn create_index(
+    client: &Client,
+    db_name: &str,
+    collection_name: &str,
+    mut spec: Document,
+) -> Result<String, mongodb::error::Error> {
+    let name = match spec.get_str("name") {
+        Ok(name) => name.to_string(),
+        Err(_) => {
+            let name = spec
+                .get_document("key")
+                .map(default_index_name)
+                .unwrap_or_default();
+            spec.insert("name", name.clone());
+            name
+        }
+    };
+
+    client
+        .database(db_name)
+        .run_command(doc! { "createIndexes": collection_name, "indexes": [spec] })
+        .await?;
+    Ok(name)
+}
+
+pub async fn drop_index(
+    client: &Client,
+    db_name: &str,
+    collection_name: &str,
+    index_name: &str,
+) -> Result<(), mongodb::error::Error> {
+    client
+        .database(db_name)
+        .collection::<Document>(collection_name)
+        .drop_index(index_name)
+        .await
+}
+
+pub async fn set_index_hidden(
+    client: &Client,
+    db_name: &str,
+    collection_name: &str,
+    index_name: &str,
+    hidden: bool,
+) -> Result<(), mongodb::error::Error> {
+    client
+        .database(db_name)
+        .run_command(doc! {
+            "collMod": collection_name,
+            "index": { "name": index_name, "hidden": hidden },
+        })
+        .await
+        .map(|_| ())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{IndexInfo, IndexUsage, default_index_name, redundant_indexes};
+    use mongodb::bson::{DateTime, Document, doc};
+
+    fn index(name: &str, keys: Document, options: Document) -> IndexInfo {
+        let mut spec = doc! { "name": name, "key": keys.clone() };
+        spec.extend(options);
+        IndexInfo {
+            name: name.to_string(),
+            keys,
+            spec,
+            size: None,
+        }
+    }
+
+    #[test]
+    fn reports_plain_prefix_indexes_as_redundant() {
+        let indexes = vec![
+            index("_id_", doc! { "_id": 1 }, doc! {}),
+            index("a_1", doc! { "a": 1 }, doc! {}),
+            index("a_-1", doc! { "a": -1 }, doc! {}),
+            index("b_1", doc! { "b": 1 }, doc! { "unique": true }),
+            index("a_1_b_1", doc! { "a": 1, "b": 1 }, doc! {}),
+            index("b_1_a_1", doc! { "b": 1, "a": 1 }, doc! {}),
+            index("c_1", doc! { "c": 1 }, doc! {}),
+            index("c_1_d_1", doc! { "c": 1.0, "d": 1 }, doc! {}),
+            index("e_1", doc! { "e": 1 }, doc! {}),
+            index(
+                "e_1_f_1",
+                doc! { "e": 1, "f": 1 },
+                doc! { "partialFilterExpression": { "f": { "$exists": true } } },
+            ),
+            index("e_1_g_1", doc! { "e": 1, "g": 1 }, doc! { "hidden": true }),
+        ];
+        assert_eq!(
+            redundant_indexes(&indexes),
+            vec![
+                ("a_1".to_string(), "a_1_b_1".to_string()),
+                ("c_1".to_string(), "c_1_d_1".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn judges_usage_only_after_the_minimum_stats_window() {
+        let now = DateTime::from_millis(30 * 24 * 60 * 60 * 1000);
+        let usage = |days: i64| IndexUsage {
+            name: "a_1".to_string(),
+            ops: 0,
+            since: Some(DateTime::from_millis(
+                now.timestamp_millis() - days * 24 * 60 * 60 * 1000,
+            )),
+        };
+        assert!(usage(7).has_mature_stats(now));
+        assert!(!usage(2).has_mature_stats(now));
+        assert!(
+            !IndexUsage {
+                since: None,
+                ..usage(7)
+            }
+            .has_mature_stats(now)
+        );
+    }
+
+    #[test]
+    fn names_indexes_like_the_server() {
+        assert_eq!(default_index_name(&doc! { "a": 1, "b": -1 }), "a_1_b_-1");
+        assert_eq!(default_index_name(&doc! { "title": "text" }), "title_text");
+    }
+}
@@ -1,3 +1,19 @@
 pub mod client;
+pub mod copy;
+pub mod csv;
 pub mod handler;
 pub mod import_export;
+pub mod indexes;
+pub mod ops;
+pub mod preview;
+pub mod profiler;
+pub mod rename;
+pub mod replset;
+pub mod schema;
+pub mod server_status;
+pub mod staging;
+pub mod stats;
+pub mod truncate;
+pub mod users;
+pub mod validation;
+pub mod views;
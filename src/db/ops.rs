@@ -0,0 +1,147 @@
+use mongodb::{
+    Client,
+    bson::{Bson, Document, doc},
+};
+
+const SUMMARY_CHAR_LIMIT: usize = 80;
+
+pub struct OperationInfo {
+    pub opid: Bson,
+    pub ns: String,
+    pub op: String,
+    pub running_ms: i64,
+    pub client: String,
+    /// The command (or plan summary) shortened to one line.
+    pub summary: String,
+}
+
+#[derive(Clone, Default)]
+pub struct OperationFilter {
+    /// Substring the namespace has to contain; empty matches everything.
+    pub namespace: String,
+    pub min_ms: i64,
+}
+
+impl OperationFilter {
+    fn matches(&self, operation: &OperationInfo) -> bool {
+        operation.ns.contains(&self.namespace) && operation.running_ms >= self.min_ms
+    }
+}
+
+fn number(document: &Document, key: &str) -> Option<i64> {
+    match document.get(key)? {
+        Bson::Int32(value) => Some(i64::from(*value)),
+        Bson::Int64(value) => Some(*value),
+        Bson::Double(value) => Some(*value as i64),
+        _ => None,
+    }
+}
+
+fn operation_info(document: &Document) -> Option<OperationInfo> {
+    let opid = document.get("opid")?.clone();
+    let running_ms = number(document, "microsecs_running")
+        .map(|micros| micros / 1000)
+        .or_else(|| number(document, "secs_running").map(|secs| secs * 1000))
+        .unwrap_or(0);
+    let client = document
+        .get_str("client")
+        .or_else(|_| document.get_str("client_s"))
+        .unwrap_or("-")
+        .to_string();
+
+    let mut summary = match document.get_document("command") {
+        Ok(command) => {
+            let mut command = command.clone();
+            for key in ["lsid", "$clusterTime", "$db", "$readPreference"] {
+                command.remove(key);
+            }
+            command.to_string()
+        }
+        Err(_) => document.get_str("desc").unwrap_or_default().to_string(),
+    };
+    if let Ok(plan) = document.get_str("planSummary") {
+        summary = format!("{plan} {summary}");
+    }
+    if summary.chars().count() > SUMMARY_CHAR_LIMIT {
+        summary = summary.chars().take(SUMMARY_CHAR_LIMIT).collect::<String>() + "…";
+    }
+
+    Some(OperationInfo {
+        opid,
+        ns: document.get_str("ns").unwrap_or_default().to_string(),
+        op: document.get_str("op").unwrap_or("-").to_string(),
+        running_ms,
+        client,
+        summary,
+    })
+}
+
+/// Lists active operations matching `filter`, longest running first.
+pub async fn current_ops(
+    client: &Client,
+    filter: &OperationFilter,
+) -> Result<Vec<OperationInfo>, mongodb::error::Error> {
+    let reply = client
+        .database("admin")
+        .run_command(doc! { "currentOp": 1, "active": true })
+        .await?;
+
+    let mut operations: Vec<OperationInfo> = reply
+        .get_array("inprog")
+        .map(|ops| {
+            ops.iter()
+                .filter_map(Bson::as_document)
+                .filter_map(operation_info)
+                .filter(|operation| filter.matches(operation))
+                .collect()
+        })
+        .unwrap_or_default();
+    operations.sort_by_key(|operation| std::cmp::Reverse(operation.running_ms));
+    Ok(operations)
+}
+
+pub async fn kill_op(client: &Client, opid: Bson) -> Result<(), mongodb::error::Error> {
+    client
+        .database("admin")
+        .run_command(doc! { "killOp": 1, "op": opid })
+        .await
+        .map(|_| ())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{OperationFilter, operation_info};
+    use mongodb::bson::doc;
+
+    #[test]
+    fn summarizes_and_filters_operations() {
+        let operation = operation_info(&doc! {
+            "opid": 42,
+            "op": "query",
+            "ns": "shop.orders",
+            "microsecs_running": 2_500_000_i64,
+            "client": "127.0.0.1:5000",
+            "planSummary": "COLLSCAN",
+            "command": { "find": "orders", "filter": { "qty": 1 }, "lsid": { "id": 1 } },
+        })
+        .unwrap();
+        assert_eq!(operation.running_ms, 2500);
+        assert_eq!(
+            operation.summary,
+            r#"COLLSCAN { "find": "orders", "filter": { "qty": 1 } }"#
+        );
+
+        let filter = OperationFilter {
+            namespace: "shop".to_string(),
+            min_ms: 1000,
+        };
+        assert!(filter.matches(&operation));
+        assert!(
+            !OperationFilter {
+                min_ms: 5000,
+                ..filter
+            }
+            .matches(&operation)
+        );
+    }
+}
@@ -0,0 +1,491 @@
+use futures::stream::TryStreamExt;
+use mongodb::{
+    Client,
+    bson::{Bson, DateTime, Document, Timestamp, doc},
+    options::UpdateModifications,
+};
+use std::cmp::Ordering;
+
+pub const PREVIEW_SAMPLE_SIZE: i64 = 5;
+
+pub const CONVERSION_FAILURE_LIMIT: i64 = 20;
+
+const CONVERSION_FAILED: &str = "__mongodbtui_conversion_failed__";
+
+pub type PreviewSample = (Document, Result<Document, String>);
+
+pub struct FieldChange {
+    pub path: String,
+    pub before: Option<Bson>,
+    pub after: Option<Bson>,
+}
+
+/// Loads a sample of matching documents and pairs each with the result of the
+/// update. Operator documents are applied locally; pipelines run server-side
+/// through an aggregation over the same sample, so nothing is written.
+pub async fn preview_update(
+    client: &Client,
+    db_name: &str,
+    collection_name: &str,
+    filter: &Document,
+    update: &UpdateModifications,
+) -> Result<Vec<PreviewSample>, mongodb::error::Error> {
+    let collection = client
+        .database(db_name)
+        .collection::<Document>(collection_name);
+    let sample_stages = vec![
+        doc! { "$match": filter.clone() },
+        doc! { "$sort": { "_id": 1 } },
+        doc! { "$limit": PREVIEW_SAMPLE_SIZE },
+    ];
+    let before: Vec<Document> = collection
+        .aggregate(sample_stages.clone())
+        .await?
+        .try_collect()
+        .await?;
+
+    let samples = match update {
+        UpdateModifications::Document(operators) => before
+            .into_iter()
+            .map(|doc| {
+                let after = apply_update_operators(&doc, operators);
+                (doc, after)
+            })
+            .collect(),
+        UpdateModifications::Pipeline(pipeline) => {
+            let stages = sample_stages.into_iter().chain(pipeline.iter().cloned());
+            let after: Vec<Document> = collection.aggregate(stages).await?.try_collect().await?;
+            let mut after = after.into_iter();
+            before
+                .into_iter()
+                .map(|doc| {
+                    let result = after
+                        .next()
+                        .ok_or_else(|| "The pipeline removed this document.".to_string());
+                    (doc, result)
+                })
+                .collect()
+        }
+        _ => before
+            .into_iter()
+            .map(|doc| (doc, Err("Unsupported update form.".to_string())))
+            .collect(),
+    };
+
+    Ok(samples)
+}
+
+/// Builds the update pipeline that converts `path` to `to`, keeping values
+/// that cannot be converted unchanged.
+pub fn convert_field_pipeline(path: &str, to: &str) -> Vec<Document> {
+    let input = format!("${path}");
+    vec![doc! {
+        "$set": {
+            path: {
+                "$convert": { "input": &input, "to": to, "onError": &input, "onNull": Bson::Null }
+            }
+        }
+    }]
+}
+
+/// Returns up to `CONVERSION_FAILURE_LIMIT` documents (`_id` and `value`)
+/// whose `path` cannot be converted to `to`.
+pub async fn conversion_failures(
+    client: &Client,
+    db_name: &str,
+    collection_name: &str,
+    filter: &Document,
+    path: &str,
+    to: &str,
+) -> Result<Vec<Document>, mongodb::error::Error> {
+    let input = format!("${path}");
+    let pipeline = vec![
+        doc! { "$match": filter.clone() },
+        doc! {
+            "$project": {
+                "value": &input,
+                "converted": {
+                    "$convert": { "input": &input, "to": to, "onError": CONVERSION_FAILED }
+                }
+            }
+        },
+        doc! { "$match": { "converted": CONVERSION_FAILED } },
+        doc! { "$project": { "converted": 0 } },
+        doc! { "$limit": CONVERSION_FAILURE_LIMIT },
+    ];
+
+    client
+        .database(db_name)
+        .collection::<Document>(collection_name)
+        .aggregate(pipeline)
+        .await?
+        .try_collect()
+        .await
+}
+
+pub fn apply_update_operators(original: &Document, update: &Document) -> Result<Document, String> {
+    let mut doc = original.clone();
+
+    for (operator, fields) in update {
+        let Bson::Document(fields) = fields else {
+            return Err(format!("{operator} expects a document of fields."));
+        };
+
+        for (path, value) in fields {
+            if path == "_id" || path.starts_with("_id.") {
+                return Err("Updates cannot modify _id.".to_string());
+            }
+            if path.split('.').any(|segment| segment.starts_with('$')) {
+                return Err(format!(
+                    "Preview does not support positional paths such as '{path}'."
+                ));
+            }
+
+            match operator.as_str() {
+                "$set" => set_path(&mut doc, path, value.clone())?,
+                "$unset" => {
+                    remove_path(&mut doc, path);
+                }
+                "$setOnInsert" => {}
+                "$inc" => {
+                    let current = get_path(&doc, path).cloned().unwrap_or(Bson::Int32(0));
+                    set_path(
+                        &mut doc,
+                        path,
+                        numeric_op(&current, value, path, NumericOp::Add)?,
+                    )?;
+                }
+                "$mul" => {
+                    let current = get_path(&doc, path).cloned().unwrap_or(Bson::Int32(0));
+                    set_path(
+                        &mut doc,
+                        path,
+                        numeric_op(&current, value, path, NumericOp::Mul)?,
+                    )?;
+                }
+                "$min" | "$max" => {
+                    let replace = match get_path(&doc, path) {
+                        None => true,
+                        Some(current) => {
+                            let ordering = compare_bson(value, current).ok_or_else(|| {
+                                format!("{operator} cannot compare the values of '{path}'.")
+                            })?;
+                            if operator == "$min" {
+                                ordering == Ordering::Less
+                            } else {
+                                ordering == Ordering::Greater
+                            }
+                        }
+                    };
+                    if replace {
+                        set_path(&mut doc, path, value.clone())?;
+                    }
+                }
+                "$rename" => {
+                    let Bson::String(target) = value else {
+                        return Err(format!("$rename target for '{path}' must be a string."));
+                    };
+                    if let Some(moved) = remove_path(&mut doc, path) {
+                        set_path(&mut doc, target, moved)?;
+                    }
+                }
+                "$currentDate" => {
+                    let now = match value {
+                        Bson::Document(spec) if spec.get_str("$type") == Ok("timestamp") => {
+                            Bson::Timestamp(Timestamp {
+                                time: (DateTime::now().timestamp_millis() / 1000) as u32,
+                                increment: 1,
+                            })
+                        }
+                        _ => Bson::DateTime(DateTime::now()),
+                    };
+                    set_path(&mut doc, path, now)?;
+                }
+                "$push" | "$addToSet" => {
+                    let items = match value {
+                        Bson::Document(spec) if spec.contains_key("$each") => {
+                            match spec.get("$each") {
+                                Some(Bson::Array(items)) => items.clone(),
+                                _ => return Err(format!("{operator}.$each must be an array.")),
+                            }
+                        }
+                        other => vec![other.clone()],
+                    };
+                    let mut array = match get_path(&doc, path) {
+                        None => Vec::new(),
+                        Some(Bson::Array(array)) => array.clone(),
+                        Some(_) => {
+                            return Err(format!("{operator} requires '{path}' to be an array."));
+                        }
+                    };
+                    for item in items {
+                        if operator == "$push" || !array.contains(&item) {
+                            array.push(item);
+                        }
+                    }
+                    set_path(&mut doc, path, Bson::Array(array))?;
+                }
+                "$pull" => {
+                    if let Some(Bson::Array(array)) = get_path(&doc, path) {
+                        if matches!(value, Bson::Document(spec) if spec.keys().any(|k| k.starts_with('$')))
+                        {
+                            return Err("Preview only supports $pull by exact value.".to_string());
+                        }
+                        let kept = array
+                            .iter()
+                            .filter(|item| *item != value)
+                            .cloned()
+                            .collect();
+                        set_path(&mut doc, path, Bson::Array(kept))?;
+                    }
+                }
+                "$pop" => {
+                    if let Some(Bson::Array(array)) = get_path(&doc, path) {
+                        let mut array = array.clone();
+                        if as_f64(value).is_some_and(|direction| direction < 0.0) {
+                            if !array.is_empty() {
+                                array.remove(0);
+                            }
+                        } else {
+                            array.pop();
+                        }
+                        set_path(&mut doc, path, Bson::Array(array))?;
+                    }
+                }
+                other => return Err(format!("Preview does not support the {other} operator.")),
+            }
+        }
+    }
+
+    Ok(doc)
+}
+
+pub fn diff_documents(before: &Document, after: &Document) -> Vec<FieldChange> {
+    let mut changes = Vec::new();
+    diff_into("", before, after, &mut changes);
+    changes
+}
+
+fn diff_into(prefix: &str, before: &Document, after: &Document, changes: &mut Vec<FieldChange>) {
+    let join = |key: &str| {
+        if prefix.is_empty() {
+            key.to_string()
+        } else {
+            format!("{prefix}.{key}")
+        }
+    };
+
+    for (key, old_value) in before {
+        match after.get(key) {
+            None => changes.push(FieldChange {
+                path: join(key),
+                before: Some(old_value.clone()),
+                after: None,
+            }),
+            Some(new_value) if new_value != old_value => match (old_value, new_value) {
+                (Bson::Document(old_doc), Bson::Document(new_doc)) => {
+                    diff_into(&join(key), old_doc, new_doc, changes)
+                }
+                _ => changes.push(FieldChange {
+                    path: join(key),
+                    before: Some(old_value.clone()),
+                    after: Some(new_value.clone()),
+                }),
+            },
+            Some(_) => {}
+        }
+    }
+
+    for (key, new_value) in after {
+        if !before.contains_key(key) {
+            changes.push(FieldChange {
+                path: join(key),
+                before: None,
+                after: Some(new_value.clone()),
+            });
+        }
+    }
+}
+
+pub fn get_path<'a>(doc: &'a Document, path: &str) -> Option<&'a Bson> {
+    let mut segments = path.split('.');
+    let mut current = doc.get(segments.next()?)?;
+    for segment in segments {
+        current = match current {
+            Bson::Document(inner) => inner.get(segment)?,
+            Bson::Array(items) => items.get(segment.parse::<usize>().ok()?)?,
+            _ => return None,
+        };
+    }
+    Some(current)
+}
+
+fn set_path(doc: &mut Document, path: &str, value: Bson) -> Result<(), String> {
+    match path.split_once('.') {
+        None => {
+            doc.insert(path, value);
+            Ok(())
+        }
+        Some((head, rest)) => {
+            let child = doc
+                .entry(head.to_string())
+                .or_insert_with(|| Bson::Document(Document::new()));
+            set_in_value(child, rest, value, path)
+        }
+    }
+}
+
+fn set_in_value(target: &mut Bson, path: &str, value: Bson, full_path: &str) -> Result<(), String> {
+    match target {
+        Bson::Document(inner) => set_path(inner, path, value),
+        Bson::Array(items) => {
+            let (head, rest) = match path.split_once('.') {
+                Some((head, rest)) => (head, Some(rest)),
+                None => (path, None),
+            };
+            let index = head
+                .parse::<usize>()
+                .map_err(|_| format!("'{full_path}' uses a non-numeric index into an array."))?;
+            while items.len() <= index {
+                items.push(Bson::Null);
+            }
+            match rest {
+                None => {
+                    items[index] = value;
+                    Ok(())
+                }
+                Some(rest) => {
+                    if !matches!(items[index], Bson::Document(_) | Bson::Array(_)) {
+                        items[index] = Bson::Document(Document::new());
+                    }
+                    set_in_value(&mut items[index], rest, value, full_path)
+                }
+            }
+        }
+        _ => Err(format!(
+            "Cannot create field in '{full_path}' because its parent is not a document."
+        )),
+    }
+}
+
+fn remove_path(doc: &mut Document, path: &str) -> Option<Bson> {
+    match path.split_once('.') {
+        None => doc.remove(path),
+        Some((head, rest)) => match doc.get_mut(head)? {
+            Bson::Document(inner) => remove_path(inner, rest),
+            _ => None,
+        },
+    }
+}
+
+#[derive(Clone, Copy)]
+enum NumericOp {
+    Add,
+    Mul,
+}
+
+fn numeric_op(current: &Bson, operand: &Bson, path: &str, op: NumericOp) -> Result<Bson, String> {
+    let apply_i64 = |a: i64, b: i64| match op {
+        NumericOp::Add => a.checked_add(b),
+        NumericOp::Mul => a.checked_mul(b),
+    };
+    let apply_f64 = |a: f64, b: f64| match op {
+        NumericOp::Add => a + b,
+        NumericOp::Mul => a * b,
+    };
+
+    match (current, operand) {
+        (Bson::Int32(a), Bson::Int32(b)) => match apply_i64(*a as i64, *b as i64) {
+            Some(result) => Ok(i32::try_from(result)
+                .map(Bson::Int32)
+                .unwrap_or(Bson::Int64(result))),
+            None => Err(format!("Integer overflow on '{path}'.")),
+        },
+        (Bson::Int32(_) | Bson::Int64(_), Bson::Int32(_) | Bson::Int64(_)) => {
+            let (a, b) = (as_i64(current), as_i64(operand));
+            apply_i64(a, b)
+                .map(Bson::Int64)
+                .ok_or_else(|| format!("Integer overflow on '{path}'."))
+        }
+        _ => match (as_f64(current), as_f64(operand)) {
+            (Some(a), Some(b)) => Ok(Bson::Double(apply_f64(a, b))),
+            _ => Err(format!(
+                "Cannot apply a numeric update to non-numeric '{path}'."
+            )),
+        },
+    }
+}
+
+fn as_i64(value: &Bson) -> i64 {
+    match value {
+        Bson::Int32(v) => *v as i64,
+        Bson::Int64(v) => *v,
+        _ => 0,
+    }
+}
+
+fn as_f64(value: &Bson) -> Option<f64> {
+    match value {
+        Bson::Int32(v) => Some(*v as f64),
+        Bson::Int64(v) => Some(*v as f64),
+        Bson::Double(v) => Some(*v),
+        _ => None,
+    }
+}
+
+fn compare_bson(a: &Bson, b: &Bson) -> Option<Ordering> {
+    match (a, b) {
+        (Bson::String(a), Bson::String(b)) => Some(a.cmp(b)),
+        (Bson::DateTime(a), Bson::DateTime(b)) => Some(a.cmp(b)),
+        (Bson::Boolean(a), Bson::Boolean(b)) => Some(a.cmp(b)),
+        _ => as_f64(a)?.partial_cmp(&as_f64(b)?),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{apply_update_operators, diff_documents};
+    use mongodb::bson::{Bson, doc};
+
+    #[test]
+    fn applies_set_inc_and_rename() {
+        let original = doc! { "_id": 1, "status": "new", "count": 2, "old": "x" };
+        let update = doc! {
+            "$set": { "status": "done", "meta.by": "ops" },
+            "$inc": { "count": 3 },
+            "$rename": { "old": "renamed" },
+        };
+
+        let updated = apply_update_operators(&original, &update).unwrap();
+
+        assert_eq!(updated.get_str("status"), Ok("done"));
+        assert_eq!(updated.get_i32("count"), Ok(5));
+        assert_eq!(
+            updated.get_document("meta").unwrap().get_str("by"),
+            Ok("ops")
+        );
+        assert!(!updated.contains_key("old"));
+        assert_eq!(updated.get_str("renamed"), Ok("x"));
+    }
+
+    #[test]
+    fn rejects_unknown_operators_and_id_changes() {
+        let original = doc! { "_id": 1 };
+
+        assert!(apply_update_operators(&original, &doc! { "$bit": { "a": 1 } }).is_err());
+        assert!(apply_update_operators(&original, &doc! { "$set": { "_id": 2 } }).is_err());
+    }
+
+    #[test]
+    fn diffs_nested_changes_by_path() {
+        let before = doc! { "a": 1, "nested": { "b": 2, "c": 3 }, "gone": true };
+        let after = doc! { "a": 1, "nested": { "b": 4, "c": 3 }, "added": "y" };
+
+        let changes = diff_documents(&before, &after);
+        let paths: Vec<_> = changes.iter().map(|change| change.path.as_str()).collect();
+
+        assert_eq!(paths, vec!["nested.b", "gone", "added"]);
+        assert_eq!(changes[0].after, Some(Bson::Int32(4)));
+        assert_eq!(changes[1].after, None);
+    }
+}
@@ -0,0 +1,227 @@
+use futures::stream::TryStreamExt;
+use mongodb::{
+    Client,
+    bson::{Bson, DateTime, Document, doc},
+};
+use std::cmp::Reverse;
+use std::collections::HashMap;
+
+/// How many of the newest `system.profile` entries are loaded.
+pub const PROFILE_ENTRY_LIMIT: i64 = 500;
+
+pub const PROFILE_LEVELS: [&str; 3] = ["off", "slow", "all"];
+
+pub struct ProfileStatus {
+    pub level: i32,
+    pub slowms: i64,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum ProfileSort {
+    Newest,
+    Duration,
+    DocsExamined,
+    KeysExamined,
+}
+
+impl ProfileSort {
+    pub fn next(self) -> Self {
+        match self {
+            Self::Newest => Self::Duration,
+            Self::Duration => Self::DocsExamined,
+            Self::DocsExamined => Self::KeysExamined,
+            Self::KeysExamined => Self::Newest,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Newest => "newest",
+            Self::Duration => "duration",
+            Self::DocsExamined => "docsExamined",
+            Self::KeysExamined => "keysExamined",
+        }
+    }
+}
+
+pub struct ProfileEntry {
+    pub ts: Option<DateTime>,
+    pub millis: i64,
+    pub ns: String,
+    pub op: String,
+    pub plan_summary: String,
+    pub docs_examined: i64,
+    pub keys_examined: i64,
+    pub nreturned: i64,
+    pub shape: String,
+}
+
+pub struct ShapeSummary {
+    pub shape: String,
+    pub count: usize,
+    pub total_ms: i64,
+    pub max_ms: i64,
+    pub docs_examined: i64,
+}
+
+fn number(document: &Document, key: &str) -> i64 {
+    match document.get(key) {
+        Some(Bson::Int32(value)) => i64::from(*value),
+        Some(Bson::Int64(value)) => *value,
+        Some(Bson::Double(value)) => *value as i64,
+        _ => 0,
+    }
+}
+
+pub async fn profile_status(
+    client: &Client,
+    db_name: &str,
+) -> Result<ProfileStatus, mongodb::error::Error> {
+    let reply = client
+        .database(db_name)
+        .run_command(doc! { "profile": -1 })
+        .await?;
+    Ok(ProfileStatus {
+        level: number(&reply, "was") as i32,
+        slowms: number(&reply, "slowms"),
+    })
+}
+
+pub async fn set_profile(
+    client: &Client,
+    db_name: &str,
+    level: i32,
+    slowms: i64,
+) -> Result<(), mongodb::error::Error> {
+    client
+        .database(db_name)
+        .run_command(doc! { "profile": level, "slowms": slowms })
+        .await
+        .map(|_| ())
+}
+
+/// Replaces every value with `?` so operations that differ only in their
+/// literals share a shape.
+fn shape_of(value: &Bson) -> Bson {
+    match value {
+        Bson::Document(document) => Bson::Document(
+            document
+                .iter()
+                .map(|(key, value)| (key.clone(), shape_of(value)))
+                .collect(),
+        ),
+        Bson::Array(items) if items.iter().any(|item| matches!(item, Bson::Document(_))) => {
+            Bson::Array(items.iter().map(shape_of).collect())
+        }
+        _ => Bson::String("?".to_string()),
+    }
+}
+
+/// Describes an entry by op, namespace and the shape of its filter, sort and
+/// pipeline.
+pub fn query_shape(entry: &Document) -> String {
+    let op = entry.get_str("op").unwrap_or("-");
+    let ns = entry.get_str("ns").unwrap_or("-");
+    let command = entry.get_document("command").cloned().unwrap_or_default();
+
+    let mut parts = Document::new();
+    for key in [
+        "filter", "q", "query", "sort", "pipeline", "updates", "deletes",
+    ] {
+        if let Some(value) = command.get(key) {
+            parts.insert(key, shape_of(value));
+        }
+    }
+    format!("{op} {ns} {parts}")
+}
+
+pub async fn load_profile(
+    client: &Client,
+    db_name: &str,
+) -> Result<Vec<ProfileEntry>, mongodb::error::Error> {
+    let documents: Vec<Document> = client
+        .database(db_name)
+        .collection::<Document>("system.profile")
+        .find(doc! {})
+        .sort(doc! { "$natural": -1 })
+        .limit(PROFILE_ENTRY_LIMIT)
+        .await?
+        .try_collect()
+        .await?;
+
+    Ok(documents
+        .into_iter()
+        .map(|document| ProfileEntry {
+            ts: document.get_datetime("ts").ok().copied(),
+            millis: number(&document, "millis"),
+            ns: document.get_str("ns").unwrap_or_default().to_string(),
+            op: document.get_str("op").unwrap_or_default().to_string(),
+            plan_summary: document
+                .get_str("planSummary")
+                .unwrap_or_default()
+                .to_string(),
+            docs_examined: number(&document, "docsExamined"),
+            keys_examined: number(&document, "keysExamined"),
+            nreturned: number(&document, "nreturned"),
+            shape: query_shape(&document),
+        })
+        .collect())
+}
+
+pub fn sort_entries(entries: &mut [ProfileEntry], sort: ProfileSort) {
+    match sort {
+        ProfileSort::Newest => entries.sort_by_key(|entry| Reverse(entry.ts)),
+        ProfileSort::Duration => entries.sort_by_key(|entry| Reverse(entry.millis)),
+        ProfileSort::DocsExamined => entries.sort_by_key(|entry| Reverse(entry.docs_examined)),
+        ProfileSort::KeysExamined => entries.sort_by_key(|entry| Reverse(entry.keys_examined)),
+    }
+}
+
+/// Groups entries by query shape, most total time first.
+pub fn group_by_shape(entries: &[ProfileEntry]) -> Vec<ShapeSummary> {
+    let mut groups: HashMap<&str, ShapeSummary> = HashMap::new();
+    for entry in entries {
+        let summary = groups
+            .entry(entry.shape.as_str())
+            .or_insert_with(|| ShapeSummary {
+                shape: entry.shape.clone(),
+                count: 0,
+                total_ms: 0,
+                max_ms: 0,
+                docs_examined: 0,
+            });
+        summary.count += 1;
+        summary.total_ms += entry.millis;
+        summary.max_ms = summary.max_ms.max(entry.millis);
+        summary.docs_examined += entry.docs_examined;
+    }
+    let mut groups: Vec<ShapeSummary> = groups.into_values().collect();
+    groups.sort_by(|a, b| {
+        b.total_ms
+            .cmp(&a.total_ms)
+            .then_with(|| a.shape.cmp(&b.shape))
+    });
+    groups
+}
+
+#[cfg(test)]
+mod tests {
+    use super::query_shape;
+    use mongodb::bson::doc;
+
+    #[test]
+    fn shapes_ignore_literal_values() {
+        let entry = |qty: i32, sku: &str| {
+            doc! {
+                "op": "query",
+                "ns": "shop.orders",
+                "command": { "find": "orders", "filter": { "qty": { "$gt": qty }, "sku": sku }, "limit": 5 },
+            }
+        };
+        assert_eq!(query_shape(&entry(1, "a")), query_shape(&entry(9, "b")));
+        assert_eq!(
+            query_shape(&entry(1, "a")),
+            r#"query shop.orders { "filter": { "qty": { "$gt": "?" }, "sku": "?" } }"#
+        );
+    }
+}
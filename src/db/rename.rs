@@ -0,0 +1,237 @@
+use super::copy::{CopyJob, CopyMode, CopyOptions};
+use super::views::{ViewInfo, list_views};
+use futures::stream::TryStreamExt;
+use mongodb::{
+    Client,
+    bson::{Document, doc},
+    error::{Error, ErrorKind},
+    options::CreateCollectionOptions,
+};
+use std::collections::VecDeque;
+
+/// `IllegalOperation` and `CommandNotSupported`: `renameCollection` cannot
+/// move this collection across databases, so it is copied instead.
+const RENAME_UNSUPPORTED_CODES: [i32; 2] = [20, 115];
+
+struct MovedCollection {
+    name: String,
+    count: u64,
+    /// Renamed into the target (moved back on rollback) rather than copied
+    /// (dropped from the target on rollback).
+    renamed: bool,
+}
+
+/// Renames a database one collection per step: collections are moved with
+/// `renameCollection` or copied, views are recreated, counts are verified
+/// and only then is the source dropped.
+pub struct RenameJob {
+    client: Client,
+    pub uri: String,
+    pub source_db: String,
+    pub target_db: String,
+    /// Regular collections with their document counts, not yet started.
+    pending: VecDeque<(String, u64, CreateCollectionOptions)>,
+    views: Vec<(String, ViewInfo)>,
+    views_created: Vec<String>,
+    moved: Vec<MovedCollection>,
+    copying: Option<(String, u64, CopyJob)>,
+    pub steps: usize,
+    pub done: usize,
+}
+
+fn rename_unsupported(error: &Error) -> bool {
+    matches!(
+        &*error.kind,
+        ErrorKind::Command(command) if RENAME_UNSUPPORTED_CODES.contains(&command.code)
+    )
+}
+
+async fn rename_across(client: &Client, from: (&str, &str), to: (&str, &str)) -> Result<(), Error> {
+    client
+        .database("admin")
+        .run_command(doc! {
+            "renameCollection": format!("{}.{}", from.0, from.1),
+            "to": format!("{}.{}", to.0, to.1),
+            "dropTarget": false,
+        })
+        .await
+        .map(|_| ())
+}
+
+impl RenameJob {
+    pub async fn new(
+        client: Client,
+        uri: &str,
+        source_db: &str,
+        target_db: &str,
+    ) -> Result<Self, Error> {
+        let existing = client.database(target_db).list_collection_names().await?;
+        if !existing.is_empty() {
+            return Err(std::io::Error::other(format!(
+                "database '{target_db}' already has collections"
+            ))
+            .into());
+        }
+
+        let views: Vec<(String, ViewInfo)> =
+            list_views(&client, source_db).await?.into_iter().collect();
+        let specs: Vec<_> = client
+            .database(source_db)
+            .list_collections()
+            .await?
+            .try_collect()
+            .await?;
+
+        let mut pending = VecDeque::new();
+        for spec in specs {
+            if spec.name.starts_with("system.") || views.iter().any(|(name, _)| *name == spec.name)
+            {
+                continue;
+            }
+            let count = client
+                .database(source_db)
+                .collection::<Document>(&spec.name)
+                .count_documents(doc! {})
+                .await?;
+            pending.push_back((spec.name, count, spec.options));
+        }
+
+        Ok(Self {
+            client,
+            uri: uri.to_string(),
+            source_db: source_db.to_string(),
+            target_db: target_db.to_string(),
+            steps: pending.len() + 2,
+            pending,
+            views,
+            views_created: Vec::new(),
+            moved: Vec::new(),
+            copying: None,
+            done: 0,
+        })
+    }
+
+    /// Runs the next step. Returns `false` once the source has been dropped.
+    pub async fn step(&mut self) -> Result<bool, Error> {
+        if let Some((name, count, job)) = &mut self.copying {
+            if job.step().await? {
+                return Ok(true);
+            }
+            self.moved.push(MovedCollection {
+                name: name.clone(),
+                count: *count,
+                renamed: false,
+            });
+            self.copying = None;
+            self.done += 1;
+            return Ok(true);
+        }
+
+        if let Some((name, count, options)) = self.pending.pop_front() {
+            let from = (self.source_db.as_str(), name.as_str());
+            let to = (self.target_db.as_str(), name.as_str());
+            match rename_across(&self.client, from, to).await {
+                Ok(()) => {
+                    self.moved.push(MovedCollection {
+                        name,
+                        count,
+                        renamed: true,
+                    });
+                    self.done += 1;
+                }
+                Err(error) if rename_unsupported(&error) => {
+                    self.start_copy(name, count, options).await?;
+                }
+                Err(error) => return Err(error),
+            }
+            return Ok(true);
+        }
+
+        if self.views_created.len() < self.views.len() {
+            for (name, view) in &self.views {
+                super::views::create_view(
+                    &self.client,
+                    &self.target_db,
+                    name,
+                    &view.view_on,
+                    view.pipeline.clone(),
+                )
+                .await?;
+                self.views_created.push(name.clone());
+            }
+            self.done += 1;
+            return Ok(true);
+        }
+
+        self.verify().await?;
+        self.client.database(&self.source_db).drop().await?;
+        self.done = self.steps;
+        Ok(false)
+    }
+
+    async fn start_copy(
+        &mut self,
+        name: String,
+        count: u64,
+        options: CreateCollectionOptions,
+    ) -> Result<(), Error> {
+        super::client::create_collection(&self.client, &self.target_db, &name, options).await?;
+        let job = CopyJob::new(
+            self.client.clone(),
+            &self.source_db,
+            self.client.clone(),
+            &self.uri,
+            &self.target_db,
+            vec![(name.clone(), name.clone())],
+            CopyOptions {
+                filter: Document::new(),
+                copy_indexes: true,
+                mode: CopyMode::Merge,
+            },
+        )
+        .await?;
+        self.copying = Some((name, count, job));
+        Ok(())
+    }
+
+    async fn verify(&self) -> Result<(), Error> {
+        for moved in &self.moved {
+            let count = self
+                .client
+                .database(&self.target_db)
+                .collection::<Document>(&moved.name)
+                .count_documents(doc! {})
+                .await?;
+            if count != moved.count {
+                return Err(std::io::Error::other(format!(
+                    "{}.{} has {count} document(s), expected {}",
+                    self.target_db, moved.name, moved.count
+                ))
+                .into());
+            }
+        }
+        Ok(())
+    }
+
+    /// Undoes everything done so far: views are dropped from the target,
+    /// renamed collections are moved back and copied ones are dropped.
+    pub async fn rollback(&mut self) -> Result<(), Error> {
+        let target = self.client.database(&self.target_db);
+        for name in self.views_created.drain(..) {
+            target.collection::<Document>(&name).drop().await?;
+        }
+        if let Some((name, _, _)) = self.copying.take() {
+            target.collection::<Document>(&name).drop().await?;
+        }
+        for moved in self.moved.drain(..).rev() {
+            if moved.renamed {
+                let from = (self.target_db.as_str(), moved.name.as_str());
+                let to = (self.source_db.as_str(), moved.name.as_str());
+                rename_across(&self.client, from, to).await?;
+            } else {
+                target.collection::<Document>(&moved.name).drop().await?;
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,134 @@
+use mongodb::{
+    Client,
+    bson::{Bson, DateTime, Document, doc},
+};
+
+/// Secondaries further behind the primary than this are highlighted.
+pub const LAG_WARNING_SECS: i64 = 10;
+
+pub struct MemberStatus {
+    pub name: String,
+    pub state: String,
+    pub healthy: bool,
+    pub optime: Option<DateTime>,
+    /// Seconds behind the primary; `None` without a primary or optime.
+    pub lag_secs: Option<i64>,
+    pub sync_source: String,
+    pub is_self: bool,
+}
+
+impl MemberStatus {
+    /// Whether the member needs attention: unhealthy, in an unusual state or
+    /// lagging.
+    pub fn needs_attention(&self) -> bool {
+        !self.healthy
+            || !matches!(self.state.as_str(), "PRIMARY" | "SECONDARY" | "ARBITER")
+            || self.lag_secs.is_some_and(|lag| lag > LAG_WARNING_SECS)
+    }
+}
+
+pub struct ReplicaSetStatus {
+    pub set: String,
+    pub members: Vec<MemberStatus>,
+}
+
+/// Reads the members of a `replSetGetStatus` reply and works out how far
+/// each one is behind the primary.
+pub fn parse_status(reply: &Document) -> ReplicaSetStatus {
+    let members: Vec<&Document> = reply
+        .get_array("members")
+        .map(|members| members.iter().filter_map(Bson::as_document).collect())
+        .unwrap_or_default();
+    let optime = |member: &Document| member.get_datetime("optimeDate").ok().copied();
+    let primary_optime = members
+        .iter()
+        .find(|member| member.get_str("stateStr") == Ok("PRIMARY"))
+        .and_then(|member| optime(member));
+
+    ReplicaSetStatus {
+        set: reply.get_str("set").unwrap_or_default().to_string(),
+        members: members
+            .iter()
+            .map(|member| {
+                let state = member.get_str("stateStr").unwrap_or("?").to_string();
+                let optime = optime(member);
+                let lag_secs = match (primary_optime, optime) {
+                    _ if state == "ARBITER" => None,
+                    (Some(primary), Some(optime)) => {
+                        Some((primary.timestamp_millis() - optime.timestamp_millis()) / 1000)
+                    }
+                    _ => None,
+                };
+                let health = match member.get("health") {
+                    Some(Bson::Double(health)) => *health >= 1.0,
+                    Some(Bson::Int32(health)) => *health >= 1,
+                    Some(Bson::Int64(health)) => *health >= 1,
+                    _ => false,
+                };
+                MemberStatus {
+                    name: member.get_str("name").unwrap_or_default().to_string(),
+                    state,
+                    healthy: health,
+                    optime,
+                    lag_secs,
+                    sync_source: member
+                        .get_str("syncSourceHost")
+                        .unwrap_or_default()
+                        .to_string(),
+                    is_self: member.get_bool("self").unwrap_or(false),
+                }
+            })
+            .collect(),
+    }
+}
+
+pub async fn hello(client: &Client) -> Result<Document, mongodb::error::Error> {
+    client
+        .database("admin")
+        .run_command(doc! { "hello": 1 })
+        .await
+}
+
+pub async fn replica_set_status(
+    client: &Client,
+) -> Result<ReplicaSetStatus, mongodb::error::Error> {
+    let reply = client
+        .database("admin")
+        .run_command(doc! { "replSetGetStatus": 1 })
+        .await?;
+    Ok(parse_status(&reply))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::parse_status;
+    use mongodb::bson::{DateTime, doc};
+
+    #[test]
+    fn computes_lag_and_flags_unhealthy_members() {
+        let at = |millis: i64| DateTime::from_millis(millis);
+        let reply = doc! {
+            "set": "rs0",
+            "members": [
+                { "name": "a:27017", "stateStr": "PRIMARY", "health": 1.0, "optimeDate": at(100_000), "self": true },
+                { "name": "b:27017", "stateStr": "SECONDARY", "health": 1.0, "optimeDate": at(98_000), "syncSourceHost": "a:27017" },
+                { "name": "c:27017", "stateStr": "SECONDARY", "health": 1.0, "optimeDate": at(40_000) },
+                { "name": "d:27017", "stateStr": "(not reachable/healthy)", "health": 0.0 },
+            ],
+        };
+        let status = parse_status(&reply);
+        assert_eq!(status.set, "rs0");
+        let lags: Vec<_> = status
+            .members
+            .iter()
+            .map(|member| member.lag_secs)
+            .collect();
+        assert_eq!(lags, vec![Some(0), Some(2), Some(60), None]);
+        let attention: Vec<_> = status
+            .members
+            .iter()
+            .map(|member| member.needs_attention())
+            .collect();
+        assert_eq!(attention, vec![false, false, true, true]);
+    }
+}
@@ -0,0 +1,225 @@
+use super::validation::bson_type_name;
+use futures::stream::TryStreamExt;
+use mongodb::{
+    Client,
+    bson::{Bson, Document, doc},
+};
+use std::collections::{BTreeMap, HashSet};
+
+const EXAMPLES_PER_FIELD: usize = 3;
+const EXAMPLE_CHAR_LIMIT: usize = 40;
+
+#[derive(Default)]
+pub struct FieldSummary {
+    /// Number of sampled documents in which the path occurs at least once.
+    pub documents: usize,
+    /// Occurrences per BSON type; array elements each count once.
+    pub types: BTreeMap<&'static str, usize>,
+    pub examples: Vec<String>,
+}
+
+impl FieldSummary {
+    pub fn occurrences(&self) -> usize {
+        self.types.values().sum()
+    }
+}
+
+/// Observed fields keyed by path. Nested fields use dotted paths and array
+/// elements are reported under `field[]`.
+pub struct SchemaReport {
+    pub documents: usize,
+    pub fields: BTreeMap<String, FieldSummary>,
+}
+
+pub async fn sample_documents(
+    client: &Client,
+    db_name: &str,
+    collection_name: &str,
+    filter: Option<Document>,
+    size: i64,
+) -> Result<Vec<Document>, mongodb::error::Error> {
+    let pipeline = match filter {
+        Some(filter) => vec![doc! { "$match": filter }, doc! { "$limit": size }],
+        None => vec![doc! { "$sample": { "size": size } }],
+    };
+
+    client
+        .database(db_name)
+        .collection::<Document>(collection_name)
+        .aggregate(pipeline)
+        .await?
+        .try_collect()
+        .await
+}
+
+pub fn analyze_documents(documents: &[Document]) -> SchemaReport {
+    let mut fields = BTreeMap::new();
+    for document in documents {
+        let mut seen = HashSet::new();
+        record_document(document, "", &mut fields, &mut seen);
+    }
+    SchemaReport {
+        documents: documents.len(),
+        fields,
+    }
+}
+
+fn child_path(prefix: &str, key: &str) -> String {
+    if prefix.is_empty() {
+        key.to_string()
+    } else {
+        format!("{prefix}.{key}")
+    }
+}
+
+fn record_document(
+    document: &Document,
+    prefix: &str,
+    fields: &mut BTreeMap<String, FieldSummary>,
+    seen: &mut HashSet<String>,
+) {
+    for (key, value) in document {
+        record_value(&child_path(prefix, key), value, fields, seen);
+    }
+}
+
+fn record_value(
+    path: &str,
+    value: &Bson,
+    fields: &mut BTreeMap<String, FieldSummary>,
+    seen: &mut HashSet<String>,
+) {
+    let summary = fields.entry(path.to_string()).or_default();
+    if seen.insert(path.to_string()) {
+        summary.documents += 1;
+    }
+    *summary.types.entry(bson_type_name(value)).or_default() += 1;
+
+    match value {
+        Bson::Document(inner) => record_document(inner, path, fields, seen),
+        Bson::Array(items) => {
+            let items_path = format!("{path}[]");
+            for item in items {
+                record_value(&items_path, item, fields, seen);
+            }
+        }
+        _ => {
+            let example: String = value.to_string().chars().take(EXAMPLE_CHAR_LIMIT).collect();
+            if summary.examples.len() < EXAMPLES_PER_FIELD && !summary.examples.contains(&example) {
+                summary.examples.push(example);
+            }
+        }
+    }
+}
+
+/// Builds a `{ "$jsonSchema": ... }` validator describing the sampled fields.
+/// Fields seen in every sampled document outside arrays become `required`.
+pub fn to_json_schema(report: &SchemaReport) -> Document {
+    let mut root = object_schema(report, "", report.documents, true);
+    root.insert("bsonType", "object");
+    doc! { "$jsonSchema": root }
+}
+
+fn object_schema(
+    report: &SchemaReport,
+    prefix: &str,
+    object_count: usize,
+    track_required: bool,
+) -> Document {
+    let mut properties = Document::new();
+    let mut required = Vec::new();
+    for (path, summary) in &report.fields {
+        let key = match prefix {
+            "" => path.as_str(),
+            _ => match path
+                .strip_prefix(prefix)
+                .and_then(|rest| rest.strip_prefix('.'))
+            {
+                Some(key) => key,
+                None => continue,
+            },
+        };
+        if key.contains('.') || key.ends_with("[]") {
+            continue;
+        }
+        if track_required && summary.documents == object_count {
+            required.push(Bson::String(key.to_string()));
+        }
+        properties.insert(key, field_schema(report, path, summary, track_required));
+    }
+
+    let mut schema = Document::new();
+    if !required.is_empty() {
+        schema.insert("required", required);
+    }
+    schema.insert("properties", properties);
+    schema
+}
+
+fn field_schema(
+    report: &SchemaReport,
+    path: &str,
+    summary: &FieldSummary,
+    track_required: bool,
+) -> Document {
+    let types: Vec<&str> = summary.types.keys().copied().collect();
+    let mut schema = match types.as_slice() {
+        [single] => doc! { "bsonType": *single },
+        _ => doc! { "bsonType": types.clone() },
+    };
+
+    if let Some(objects) = summary.types.get("object") {
+        let track_required = track_required && types.len() == 1;
+        schema.extend(object_schema(report, path, *objects, track_required));
+    }
+    if summary.types.contains_key("array") {
+        let items_path = format!("{path}[]");
+        if let Some(items) = report.fields.get(&items_path) {
+            schema.insert("items", field_schema(report, &items_path, items, false));
+        }
+    }
+    schema
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{analyze_documents, to_json_schema};
+    use crate::db::validation::validate_document;
+    use mongodb::bson::doc;
+
+    #[test]
+    fn reports_types_presence_and_nested_paths() {
+        let documents = vec![
+            doc! { "name": "a", "tags": ["x", 1], "address": { "zip": "1" } },
+            doc! { "name": 2, "tags": [], "address": { "zip": "2", "city": "c" } },
+        ];
+        let report = analyze_documents(&documents);
+
+        let name = &report.fields["name"];
+        assert_eq!(name.documents, 2);
+        assert_eq!(name.types.get("string"), Some(&1));
+        assert_eq!(name.types.get("int"), Some(&1));
+        assert_eq!(report.fields["tags[]"].documents, 1);
+        assert_eq!(report.fields["tags[]"].occurrences(), 2);
+        assert_eq!(report.fields["address.city"].documents, 1);
+    }
+
+    #[test]
+    fn exported_schema_accepts_the_sampled_documents() {
+        let documents = vec![
+            doc! { "name": "a", "tags": ["x"], "address": { "zip": "1" } },
+            doc! { "name": "b", "address": { "zip": "2", "city": "c" } },
+        ];
+        let validator = to_json_schema(&analyze_documents(&documents));
+
+        for document in &documents {
+            assert!(validate_document(&validator, document).is_empty());
+        }
+        let schema = validator.get_document("$jsonSchema").unwrap();
+        assert_eq!(
+            schema.get_array("required").unwrap(),
+            &vec!["address".into(), "name".into()]
+        );
+        assert!(!validate_document(&validator, &doc! { "address": { "zip": 1 } }).is_empty());
+    }
+}
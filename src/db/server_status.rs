@@ -0,0 +1,124 @@
+use mongodb::{
+    Client,
+    bson::{Bson, Document, doc},
+};
+
+pub const OPCOUNTER_KINDS: [&str; 6] =
+    ["insert", "query", "update", "delete", "getmore", "command"];
+
+/// The `serverStatus` counters the dashboard plots.
+#[derive(Clone, Default)]
+pub struct ServerSample {
+    /// Server clock in milliseconds, used to turn counters into rates.
+    pub local_time: i64,
+    pub opcounters: [i64; OPCOUNTER_KINDS.len()],
+    pub connections: i64,
+    pub available_connections: i64,
+    pub bytes_in: i64,
+    pub bytes_out: i64,
+    pub cache_bytes: i64,
+    pub cache_max_bytes: i64,
+    pub queued_readers: i64,
+    pub queued_writers: i64,
+}
+
+impl ServerSample {
+    /// Per-second rate of a counter between two samples.
+    pub fn rate(&self, previous: &ServerSample, value: impl Fn(&ServerSample) -> i64) -> u64 {
+        let elapsed = self.local_time - previous.local_time;
+        if elapsed <= 0 {
+            return 0;
+        }
+        let delta = (value(self) - value(previous)).max(0);
+        (delta * 1000 / elapsed) as u64
+    }
+
+    pub fn total_ops(&self) -> i64 {
+        self.opcounters.iter().sum()
+    }
+
+    pub fn cache_percent(&self) -> u64 {
+        if self.cache_max_bytes <= 0 {
+            return 0;
+        }
+        (self.cache_bytes * 100 / self.cache_max_bytes) as u64
+    }
+}
+
+fn number(document: &Document, path: &[&str]) -> i64 {
+    let Some((last, parents)) = path.split_last() else {
+        return 0;
+    };
+    let mut current = document;
+    for key in parents {
+        match current.get_document(key) {
+            Ok(inner) => current = inner,
+            Err(_) => return 0,
+        }
+    }
+    match current.get(last) {
+        Some(Bson::Int32(value)) => i64::from(*value),
+        Some(Bson::Int64(value)) => *value,
+        Some(Bson::Double(value)) => *value as i64,
+        _ => 0,
+    }
+}
+
+pub fn parse_server_status(status: &Document) -> ServerSample {
+    let cache = ["wiredTiger", "cache"];
+    ServerSample {
+        local_time: status
+            .get_datetime("localTime")
+            .map(|time| time.timestamp_millis())
+            .unwrap_or_default(),
+        opcounters: OPCOUNTER_KINDS.map(|kind| number(status, &["opcounters", kind])),
+        connections: number(status, &["connections", "current"]),
+        available_connections: number(status, &["connections", "available"]),
+        bytes_in: number(status, &["network", "bytesIn"]),
+        bytes_out: number(status, &["network", "bytesOut"]),
+        cache_bytes: number(
+            status,
+            &[cache[0], cache[1], "bytes currently in the cache"],
+        ),
+        cache_max_bytes: number(status, &[cache[0], cache[1], "maximum bytes configured"]),
+        queued_readers: number(status, &["globalLock", "currentQueue", "readers"]),
+        queued_writers: number(status, &["globalLock", "currentQueue", "writers"]),
+    }
+}
+
+pub async fn server_status(client: &Client) -> Result<ServerSample, mongodb::error::Error> {
+    let status = client
+        .database("admin")
+        .run_command(doc! { "serverStatus": 1 })
+        .await?;
+    Ok(parse_server_status(&status))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::parse_server_status;
+    use mongodb::bson::{DateTime, doc};
+
+    #[test]
+    fn reads_counters_and_rates() {
+        let status = |millis: i64, queries: i64, bytes_in: i64| {
+            doc! {
+                "localTime": DateTime::from_millis(millis),
+                "opcounters": { "insert": 1, "query": queries },
+                "connections": { "current": 5, "available": 95 },
+                "network": { "bytesIn": bytes_in, "bytesOut": 0_i64 },
+                "wiredTiger": { "cache": {
+                    "bytes currently in the cache": 256_i64,
+                    "maximum bytes configured": 1024.0,
+                } },
+            }
+        };
+        let first = parse_server_status(&status(10_000, 10, 1_000));
+        let second = parse_server_status(&status(12_000, 30, 5_000));
+
+        assert_eq!(second.connections, 5);
+        assert_eq!(second.cache_percent(), 25);
+        assert_eq!(second.rate(&first, |sample| sample.total_ops()), 10);
+        assert_eq!(second.rate(&first, |sample| sample.bytes_in), 2_000);
+    }
+}
@@ -0,0 +1,249 @@
+use crate::db::client::edit_update;
+use mongodb::{
+    Client,
+    bson::{Bson, Document, doc},
+};
+
+pub enum StagedChange {
+    Insert {
+        db: String,
+        collection: String,
+        document: Document,
+    },
+    Update {
+        db: String,
+        collection: String,
+        original: Document,
+        edited: Document,
+    },
+    Delete {
+        db: String,
+        collection: String,
+        document: Document,
+    },
+}
+
+impl StagedChange {
+    pub fn namespace(&self) -> (&str, &str) {
+        match self {
+            Self::Insert { db, collection, .. }
+            | Self::Update { db, collection, .. }
+            | Self::Delete { db, collection, .. } => (db, collection),
+        }
+    }
+
+    /// The `_id` of the existing document this change targets.
+    pub fn target_id(&self) -> Option<&Bson> {
+        match self {
+            Self::Insert { .. } => None,
+            Self::Update { original, .. } => original.get("_id"),
+            Self::Delete { document, .. } => document.get("_id"),
+        }
+    }
+
+    pub fn summary(&self) -> String {
+        let (db, collection) = self.namespace();
+        let id = self
+            .target_id()
+            .map(ToString::to_string)
+            .unwrap_or_else(|| "new".to_string());
+        let action = match self {
+            Self::Insert { .. } => "insert",
+            Self::Update { .. } => "update",
+            Self::Delete { .. } => "delete",
+        };
+        format!("{action} {db}.{collection} (_id: {id})")
+    }
+
+    fn targets_same_document(&self, other: &StagedChange) -> bool {
+        self.namespace() == other.namespace()
+            && self.target_id().is_some()
+            && self.target_id() == other.target_id()
+    }
+}
+
+/// Adds a change to the buffer, folding it into an earlier change to the
+/// same document so each document is written at most once on commit.
+pub fn stage_change(changes: &mut Vec<StagedChange>, change: StagedChange) {
+    let Some(index) = changes
+        .iter()
+        .position(|existing| existing.targets_same_document(&change))
+    else {
+        changes.push(change);
+        return;
+    };
+
+    match std::mem::replace(&mut changes[index], change) {
+        StagedChange::Update { original, .. } => match &mut changes[index] {
+            StagedChange::Update {
+                original: first, ..
+            }
+            | StagedChange::Delete {
+                document: first, ..
+            } => *first = original,
+            StagedChange::Insert { .. } => {}
+        },
+        previous => changes[index] = previous,
+    }
+}
+
+/// `_id` plus the staged value of every field `update` changes, so the update
+/// misses a document that was changed by someone else after it was staged.
+fn update_filter(original: &Document, id: &Bson, update: &Document) -> Document {
+    let mut filter = doc! { "_id": id.clone() };
+    for operator in ["$set", "$unset"] {
+        let Ok(fields) = update.get_document(operator) else {
+            continue;
+        };
+        for path in fields.keys() {
+            let mut parts = path.split('.');
+            let first = parts.next().and_then(|key| original.get(key));
+            let value = parts.try_fold(first, |value, key| match value {
+                Some(Bson::Document(inner)) => Some(inner.get(key)),
+                _ => None,
+            });
+            let condition = match value.flatten() {
+                Some(value) => value.clone(),
+                None => Bson::Document(doc! { "$exists": false }),
+            };
+            filter.insert(path, condition);
+        }
+    }
+    filter
+}
+
+/// Writes every staged change inside one multi-document transaction. Either
+/// all of them are applied or, on the first error or conflict, none are.
+pub async fn commit_staged_changes(
+    client: &Client,
+    changes: &[StagedChange],
+) -> Result<usize, mongodb::error::Error> {
+    let mut session = client.start_session().await?;
+    session.start_transaction().await?;
+
+    for change in changes {
+        let (db, collection) = change.namespace();
+        let collection = client.database(db).collection::<Document>(collection);
+        let result = match change {
+            StagedChange::Insert { document, .. } => collection
+                .insert_one(document)
+                .session(&mut session)
+                .await
+                .map(|_| ()),
+            StagedChange::Update {
+                original, edited, ..
+            } => {
+                let update = edit_update(original, edited);
+                match original.get("_id") {
+                    Some(id) if !update.is_empty() => collection
+                        .update_one(update_filter(original, id, &update), update)
+                        .session(&mut session)
+                        .await
+                        .and_then(|result| {
+                            if result.matched_count > 0 {
+                                return Ok(());
+                            }
+                            Err(std::io::Error::other(format!(
+                                "conflict: {} was changed or removed after it was staged",
+                                change.summary()
+                            ))
+                            .into())
+                        }),
+                    _ => Ok(()),
+                }
+            }
+            StagedChange::Delete { document, .. } => match document.get("_id") {
+                Some(id) => collection
+                    .delete_one(doc! { "_id": id.clone() })
+                    .session(&mut session)
+                    .await
+                    .map(|_| ()),
+                None => Ok(()),
+            },
+        };
+
+        if let Err(error) = result {
+            let _ = session.abort_transaction().await;
+            return Err(error);
+        }
+    }
+
+    session.commit_transaction().await?;
+    Ok(changes.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{StagedChange, stage_change, update_filter};
+    use crate::db::client::edit_update;
+    use mongodb::bson::{Bson, doc};
+
+    fn update(original: i32, edited: i32) -> StagedChange {
+        StagedChange::Update {
+            db: "shop".to_string(),
+            collection: "orders".to_string(),
+            original: doc! { "_id": 1, "qty": original },
+            edited: doc! { "_id": 1, "qty": edited },
+        }
+    }
+
+    #[test]
+    fn folds_changes_to_the_same_document() {
+        let mut changes = Vec::new();
+        stage_change(&mut changes, update(1, 2));
+        stage_change(&mut changes, update(2, 3));
+        assert_eq!(changes.len(), 1);
+        assert!(matches!(
+            &changes[0],
+            StagedChange::Update { original, edited, .. }
+                if original.get_i32("qty") == Ok(1) && edited.get_i32("qty") == Ok(3)
+        ));
+
+        stage_change(
+            &mut changes,
+            StagedChange::Delete {
+                db: "shop".to_string(),
+                collection: "orders".to_string(),
+                document: doc! { "_id": 1, "qty": 3 },
+            },
+        );
+        assert!(matches!(
+            &changes[0],
+            StagedChange::Delete { document, .. } if document.get_i32("qty") == Ok(1)
+        ));
+    }
+
+    #[test]
+    fn filters_updates_on_the_staged_values() {
+        let original = doc! { "_id": 1, "qty": 1, "address": { "city": "Rome" }, "note": "x" };
+        let edited = doc! { "_id": 1, "qty": 2, "address": { "city": "Oslo", "zip": "0150" } };
+        let update = edit_update(&original, &edited);
+
+        assert_eq!(
+            update_filter(&original, &Bson::Int32(1), &update),
+            doc! {
+                "_id": 1,
+                "qty": 1,
+                "address.city": "Rome",
+                "address.zip": { "$exists": false },
+                "note": "x",
+            }
+        );
+    }
+
+    #[test]
+    fn keeps_inserts_separate() {
+        let mut changes = Vec::new();
+        for _ in 0..2 {
+            stage_change(
+                &mut changes,
+                StagedChange::Insert {
+                    db: "shop".to_string(),
+                    collection: "orders".to_string(),
+                    document: doc! { "qty": 1 },
+                },
+            );
+        }
+        assert_eq!(changes.len(), 2);
+    }
+}
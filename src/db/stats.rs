@@ -0,0 +1,100 @@
+use futures::stream::TryStreamExt;
+use mongodb::{
+    Client,
+    bson::{Bson, Document, doc},
+};
+
+pub struct CollectionStats {
+    pub count: i64,
+    pub size: i64,
+    pub storage_size: i64,
+    pub avg_obj_size: i64,
+    pub total_index_size: i64,
+    pub index_sizes: Vec<(String, i64)>,
+    pub capped: bool,
+    pub max_size: Option<i64>,
+    pub max_documents: Option<i64>,
+    pub timeseries: bool,
+}
+
+pub struct DatabaseStats {
+    pub collections: i64,
+    pub views: i64,
+    pub objects: i64,
+    pub avg_obj_size: i64,
+    pub data_size: i64,
+    pub storage_size: i64,
+    pub indexes: i64,
+    pub index_size: i64,
+}
+
+/// Reads a numeric statistic, which the server reports as int, long or double
+/// depending on its magnitude.
+fn number(stats: &Document, key: &str) -> Option<i64> {
+    match stats.get(key)? {
+        Bson::Int32(value) => Some(i64::from(*value)),
+        Bson::Int64(value) => Some(*value),
+        Bson::Double(value) => Some(*value as i64),
+        _ => None,
+    }
+}
+
+pub async fn collection_stats(
+    client: &Client,
+    db_name: &str,
+    collection_name: &str,
+) -> Result<CollectionStats, mongodb::error::Error> {
+    let stats = client
+        .database(db_name)
+        .collection::<Document>(collection_name)
+        .aggregate(vec![doc! { "$collStats": { "storageStats": {} } }])
+        .await?
+        .try_next()
+        .await?
+        .and_then(|stats| stats.get_document("storageStats").ok().cloned())
+        .unwrap_or_default();
+
+    let index_sizes = stats
+        .get_document("indexSizes")
+        .map(|sizes| {
+            sizes
+                .keys()
+                .map(|name| (name.clone(), number(sizes, name).unwrap_or(0)))
+                .collect()
+        })
+        .unwrap_or_default();
+
+    Ok(CollectionStats {
+        count: number(&stats, "count").unwrap_or(0),
+        size: number(&stats, "size").unwrap_or(0),
+        storage_size: number(&stats, "storageSize").unwrap_or(0),
+        avg_obj_size: number(&stats, "avgObjSize").unwrap_or(0),
+        total_index_size: number(&stats, "totalIndexSize").unwrap_or(0),
+        index_sizes,
+        capped: stats.get_bool("capped").unwrap_or(false),
+        max_size: number(&stats, "maxSize"),
+        max_documents: number(&stats, "max"),
+        timeseries: stats.contains_key("timeseries"),
+    })
+}
+
+pub async fn database_stats(
+    client: &Client,
+    db_name: &str,
+) -> Result<DatabaseStats, mongodb::error::Error> {
+    let stats = client
+        .database(db_name)
+        .run_command(doc! { "dbStats": 1 })
+        .await?;
+
+    Ok(DatabaseStats {
+        collections: number(&stats, "collections").unwrap_or(0),
+        views: number(&stats, "views").unwrap_or(0),
+        objects: number(&stats, "objects").unwrap_or(0),
+        avg_obj_size: number(&stats, "avgObjSize").unwrap_or(0),
+        data_size: number(&stats, "dataSize").unwrap_or(0),
+        storage_size: number(&stats, "storageSize").unwrap_or(0),
+        indexes: number(&stats, "indexes").unwrap_or(0),
+        index_size: number(&stats, "indexSize").unwrap_or(0),
+    })
+}
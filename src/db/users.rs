@@ -0,0 +1,271 @@
+use mongodb::{
+    Client,
+    bson::{Bson, Document, doc},
+};
+
+/// A role reference such as `readWrite@shop`.
+#[derive(Clone, PartialEq, Debug)]
+pub struct RoleRef {
+    pub role: String,
+    pub db: String,
+}
+
+impl RoleRef {
+    /// Parses `role` or `role@db`; a bare role belongs to `default_db`.
+    pub fn parse(text: &str, default_db: &str) -> Option<Self> {
+        let text = text.trim();
+        let (role, db) = text.split_once('@').unwrap_or((text, default_db));
+        (!role.is_empty() && !db.is_empty()).then(|| Self {
+            role: role.to_string(),
+            db: db.to_string(),
+        })
+    }
+
+    fn from_document(document: &Document) -> Option<Self> {
+        Some(Self {
+            role: document.get_str("role").ok()?.to_string(),
+            db: document.get_str("db").ok()?.to_string(),
+        })
+    }
+
+    fn to_document(&self) -> Document {
+        doc! { "role": &self.role, "db": &self.db }
+    }
+}
+
+impl std::fmt::Display for RoleRef {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}@{}", self.role, self.db)
+    }
+}
+
+/// Parses a comma separated role list.
+pub fn parse_roles(text: &str, default_db: &str) -> Result<Vec<RoleRef>, String> {
+    text.split(',')
+        .filter(|part| !part.trim().is_empty())
+        .map(|part| {
+            RoleRef::parse(part, default_db)
+                .ok_or_else(|| format!("'{}' is not a role.", part.trim()))
+        })
+        .collect()
+}
+
+pub struct UserInfo {
+    pub user: String,
+    pub roles: Vec<RoleRef>,
+    pub mechanisms: Vec<String>,
+}
+
+pub struct RoleInfo {
+    pub role: RoleRef,
+    pub built_in: bool,
+    /// Roles this role inherits from.
+    pub roles: Vec<RoleRef>,
+    /// `(resource, actions)` pairs.
+    pub privileges: Vec<(String, Vec<String>)>,
+}
+
+fn role_refs(document: &Document, key: &str) -> Vec<RoleRef> {
+    document
+        .get_array(key)
+        .map(|roles| {
+            roles
+                .iter()
+                .filter_map(Bson::as_document)
+                .filter_map(RoleRef::from_document)
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+fn strings(document: &Document, key: &str) -> Vec<String> {
+    document
+        .get_array(key)
+        .map(|values| {
+            values
+                .iter()
+                .filter_map(Bson::as_str)
+                .map(str::to_string)
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+fn resource_name(resource: &Document) -> String {
+    if resource.get_bool("cluster").unwrap_or(false) {
+        return "cluster".to_string();
+    }
+    if resource.get_bool("anyResource").unwrap_or(false) {
+        return "any resource".to_string();
+    }
+    let part = |key: &str| match resource.get_str(key) {
+        Ok("") | Err(_) => "*".to_string(),
+        Ok(value) => value.to_string(),
+    };
+    format!("{}.{}", part("db"), part("collection"))
+}
+
+pub async fn list_users(
+    client: &Client,
+    db_name: &str,
+) -> Result<Vec<UserInfo>, mongodb::error::Error> {
+    let reply = client
+        .database(db_name)
+        .run_command(doc! { "usersInfo": 1 })
+        .await?;
+
+    let mut users: Vec<UserInfo> = reply
+        .get_array("users")
+        .map(|users| {
+            users
+                .iter()
+                .filter_map(Bson::as_document)
+                .collect::<Vec<_>>()
+        })
+        .unwrap_or_default()
+        .into_iter()
+        .map(|user| UserInfo {
+            user: user.get_str("user").unwrap_or_default().to_string(),
+            roles: role_refs(user, "roles"),
+            mechanisms: strings(user, "mechanisms"),
+        })
+        .collect();
+    users.sort_by(|a, b| a.user.cmp(&b.user));
+    Ok(users)
+}
+
+/// Lists custom and built-in roles of a database with their privileges.
+pub async fn list_roles(
+    client: &Client,
+    db_name: &str,
+) -> Result<Vec<RoleInfo>, mongodb::error::Error> {
+    let reply = client
+        .database(db_name)
+        .run_command(doc! { "rolesInfo": 1, "showPrivileges": true, "showBuiltinRoles": true })
+        .await?;
+
+    let mut roles: Vec<RoleInfo> = reply
+        .get_array("roles")
+        .map(|roles| {
+            roles
+                .iter()
+                .filter_map(Bson::as_document)
+                .collect::<Vec<_>>()
+        })
+        .unwrap_or_default()
+        .into_iter()
+        .filter_map(|role| {
+            let privileges = role
+                .get_array("privileges")
+                .map(|privileges| {
+                    privileges
+                        .iter()
+                        .filter_map(Bson::as_document)
+                        .map(|privilege| {
+                            let resource = privilege
+                                .get_document("resource")
+                                .map(resource_name)
+                                .unwrap_or_default();
+                            (resource, strings(privilege, "actions"))
+                        })
+                        .collect()
+                })
+                .unwrap_or_default();
+            Some(RoleInfo {
+                role: RoleRef::from_document(role)?,
+                built_in: role.get_bool("isBuiltin").unwrap_or(false),
+                roles: role_refs(role, "roles"),
+                privileges,
+            })
+        })
+        .collect();
+    roles.sort_by(|a, b| (a.built_in, &a.role.role).cmp(&(b.built_in, &b.role.role)));
+    Ok(roles)
+}
+
+fn role_documents(roles: &[RoleRef]) -> Vec<Document> {
+    roles.iter().map(RoleRef::to_document).collect()
+}
+
+pub async fn create_user(
+    client: &Client,
+    db_name: &str,
+    user: &str,
+    password: &str,
+    roles: &[RoleRef],
+) -> Result<(), mongodb::error::Error> {
+    client
+        .database(db_name)
+        .run_command(doc! { "createUser": user, "pwd": password, "roles": role_documents(roles) })
+        .await
+        .map(|_| ())
+}
+
+pub async fn change_password(
+    client: &Client,
+    db_name: &str,
+    user: &str,
+    password: &str,
+) -> Result<(), mongodb::error::Error> {
+    client
+        .database(db_name)
+        .run_command(doc! { "updateUser": user, "pwd": password })
+        .await
+        .map(|_| ())
+}
+
+/// Grants the roles, or revokes them when `grant` is false.
+pub async fn set_roles(
+    client: &Client,
+    db_name: &str,
+    user: &str,
+    roles: &[RoleRef],
+    grant: bool,
+) -> Result<(), mongodb::error::Error> {
+    let command = if grant {
+        "grantRolesToUser"
+    } else {
+        "revokeRolesFromUser"
+    };
+    client
+        .database(db_name)
+        .run_command(doc! { command: user, "roles": role_documents(roles) })
+        .await
+        .map(|_| ())
+}
+
+pub async fn drop_user(
+    client: &Client,
+    db_name: &str,
+    user: &str,
+) -> Result<(), mongodb::error::Error> {
+    client
+        .database(db_name)
+        .run_command(doc! { "dropUser": user })
+        .await
+        .map(|_| ())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{RoleRef, parse_roles};
+
+    #[test]
+    fn parses_role_lists_with_optional_databases() {
+        let roles = parse_roles("readWrite, read@reporting,", "shop").unwrap();
+        assert_eq!(
+            roles,
+            vec![
+                RoleRef {
+                    role: "readWrite".to_string(),
+                    db: "shop".to_string()
+                },
+                RoleRef {
+                    role: "read".to_string(),
+                    db: "reporting".to_string()
+                },
+            ]
+        );
+        assert!(parse_roles("read@", "shop").is_err());
+    }
+}
@@ -0,0 +1,614 @@
+use futures::stream::TryStreamExt;
+use mongodb::{
+    Client,
+    bson::{Bson, Document, doc},
+    options::CreateCollectionOptions,
+};
+use std::fmt;
+
+pub struct Violation {
+    pub path: String,
+    pub message: String,
+}
+
+impl fmt::Display for Violation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let path = if self.path.is_empty() {
+            "(document)"
+        } else {
+            &self.path
+        };
+        write!(f, "{path}: {}", self.message)
+    }
+}
+
+/// A collection's validator together with how the server enforces it.
+pub struct Validation {
+    pub validator: Document,
+    pub level: String,
+    pub action: String,
+}
+
+impl Validation {
+    /// With `warn` the server accepts failing writes and only logs them.
+    pub fn warns_only(&self) -> bool {
+        self.action == "warn"
+    }
+}
+
+/// The outcome of checking one write against the collection's validator.
+#[derive(Default)]
+pub struct DocumentCheck {
+    pub violations: Vec<Violation>,
+    /// The validator only warns, so the server would accept the write.
+    pub warns_only: bool,
+}
+
+async fn collection_options(
+    client: &Client,
+    db_name: &str,
+    collection_name: &str,
+) -> Result<Option<CreateCollectionOptions>, mongodb::error::Error> {
+    let specs: Vec<_> = client
+        .database(db_name)
+        .list_collections()
+        .filter(doc! { "name": collection_name })
+        .await?
+        .try_collect()
+        .await?;
+    Ok(specs.into_iter().next().map(|spec| spec.options))
+}
+
+/// Reads the validator with its level and action, falling back to the
+/// server defaults (`strict`, `error`) when they are not set.
+async fn fetch_validation(
+    client: &Client,
+    db_name: &str,
+    collection_name: &str,
+) -> Result<Validation, mongodb::error::Error> {
+    let options = collection_options(client, db_name, collection_name).await?;
+
+    let as_string = |value: Option<Bson>, default: &str| match value {
+        Some(Bson::String(value)) => value,
+        _ => default.to_string(),
+    };
+    let level = options
+        .as_ref()
+        .and_then(|options| options.validation_level.as_ref())
+        .and_then(|level| mongodb::bson::to_bson(level).ok());
+    let action = options
+        .as_ref()
+        .and_then(|options| options.validation_action.as_ref())
+        .and_then(|action| mongodb::bson::to_bson(action).ok());
+
+    Ok(Validation {
+        level: as_string(level, "strict"),
+        action: as_string(action, "error"),
+        validator: options
+            .and_then(|options| options.validator)
+            .unwrap_or_default(),
+    })
+}
+
+/// Returns the validator writes are checked against, or `None` when the
+/// collection has none or its `validationLevel` is `off`.
+pub async fn fetch_validator(
+    client: &Client,
+    db_name: &str,
+    collection_name: &str,
+) -> Result<Option<Validation>, mongodb::error::Error> {
+    let validation = fetch_validation(client, db_name, collection_name).await?;
+    Ok(Some(validation)
+        .filter(|validation| !validation.validator.is_empty() && validation.level != "off"))
+}
+
+pub const VALIDATION_LEVELS: [&str; 3] = ["off", "strict", "moderate"];
+pub const VALIDATION_ACTIONS: [&str; 3] = ["error", "warn", "errorAndLog"];
+/// How many failing documents the violator check lists.
+pub const VIOLATOR_LIMIT: i64 = 20;
+
+/// Reads the collection's validator, `validationLevel` and `validationAction`
+/// as one document in the shape `collMod` accepts.
+pub async fn fetch_validation_rules(
+    client: &Client,
+    db_name: &str,
+    collection_name: &str,
+) -> Result<Document, mongodb::error::Error> {
+    let validation = fetch_validation(client, db_name, collection_name).await?;
+    Ok(doc! {
+        "validator": validation.validator,
+        "validationLevel": validation.level,
+        "validationAction": validation.action,
+    })
+}
+
+/// Checks edited rules before they are sent to `collMod`.
+pub fn check_validation_rules(rules: &Document) -> Result<(), String> {
+    if let Some(key) = rules
+        .keys()
+        .find(|key| !["validator", "validationLevel", "validationAction"].contains(&key.as_str()))
+    {
+        return Err(format!("Unknown key '{key}' in validation rules."));
+    }
+    if rules.get("validator").is_some() && rules.get_document("validator").is_err() {
+        return Err("validator must be an object.".to_string());
+    }
+    let allowed = |key: &str, values: &[&str]| {
+        rules
+            .get(key)
+            .is_none_or(|value| value.as_str().is_some_and(|value| values.contains(&value)))
+    };
+    if !allowed("validationLevel", &VALIDATION_LEVELS) {
+        return Err(format!(
+            "validationLevel must be one of {}.",
+            VALIDATION_LEVELS.join(", ")
+        ));
+    }
+    if !allowed("validationAction", &VALIDATION_ACTIONS) {
+        return Err(format!(
+            "validationAction must be one of {}.",
+            VALIDATION_ACTIONS.join(", ")
+        ));
+    }
+    Ok(())
+}
+
+/// Counts the documents that fail `validator` and returns up to `limit` of them.
+pub async fn find_violators(
+    client: &Client,
+    db_name: &str,
+    collection_name: &str,
+    validator: &Document,
+    limit: i64,
+) -> Result<(u64, Vec<Document>), mongodb::error::Error> {
+    let collection = client
+        .database(db_name)
+        .collection::<Document>(collection_name);
+    let filter = doc! { "$nor": [validator.clone()] };
+
+    let count = collection.count_documents(filter.clone()).await?;
+    let documents = collection
+        .find(filter)
+        .limit(limit)
+        .await?
+        .try_collect()
+        .await?;
+    Ok((count, documents))
+}
+
+pub async fn apply_validation_rules(
+    client: &Client,
+    db_name: &str,
+    collection_name: &str,
+    rules: Document,
+) -> Result<(), mongodb::error::Error> {
+    let mut command = doc! { "collMod": collection_name };
+    command.extend(rules);
+    client
+        .database(db_name)
+        .run_command(command)
+        .await
+        .map(|_| ())
+}
+
+/// Checks a document against the collection's validator before it is sent.
+/// `original` is the stored document an update replaces; under the
+/// `moderate` level an update to a document that already fails is not checked.
+/// Only the `$jsonSchema` part of a validator is evaluated locally; query
+/// expressions are left for the server to enforce.
+pub async fn check_document(
+    client: &Client,
+    db_name: &str,
+    collection_name: &str,
+    document: &Document,
+    original: Option<&Document>,
+) -> Result<DocumentCheck, mongodb::error::Error> {
+    let Some(validation) = fetch_validator(client, db_name, collection_name).await? else {
+        return Ok(DocumentCheck::default());
+    };
+    Ok(check_write(&validation, document, original))
+}
+
+pub fn check_write(
+    validation: &Validation,
+    document: &Document,
+    original: Option<&Document>,
+) -> DocumentCheck {
+    let exempt = validation.level == "moderate"
+        && original
+            .is_some_and(|original| !validate_document(&validation.validator, original).is_empty());
+    DocumentCheck {
+        violations: if exempt {
+            Vec::new()
+        } else {
+            validate_document(&validation.validator, document)
+        },
+        warns_only: validation.warns_only(),
+    }
+}
+
+pub fn validate_document(validator: &Document, document: &Document) -> Vec<Violation> {
+    let mut violations = Vec::new();
+    if let Ok(schema) = validator.get_document("$jsonSchema") {
+        check_schema(
+            schema,
+            &Bson::Document(document.clone()),
+            "",
+            &mut violations,
+        );
+    }
+    violations
+}
+
+fn child_path(path: &str, key: &str) -> String {
+    if path.is_empty() {
+        key.to_string()
+    } else {
+        format!("{path}.{key}")
+    }
+}
+
+pub fn bson_type_name(value: &Bson) -> &'static str {
+    match value {
+        Bson::Double(_) => "double",
+        Bson::String(_) => "string",
+        Bson::Document(_) => "object",
+        Bson::Array(_) => "array",
+        Bson::Binary(_) => "binData",
+        Bson::ObjectId(_) => "objectId",
+        Bson::Boolean(_) => "bool",
+        Bson::DateTime(_) => "date",
+        Bson::Null => "null",
+        Bson::RegularExpression(_) => "regex",
+        Bson::JavaScriptCode(_) => "javascript",
+        Bson::JavaScriptCodeWithScope(_) => "javascriptWithScope",
+        Bson::Int32(_) => "int",
+        Bson::Timestamp(_) => "timestamp",
+        Bson::Int64(_) => "long",
+        Bson::Decimal128(_) => "decimal",
+        Bson::MinKey => "minKey",
+        Bson::MaxKey => "maxKey",
+        Bson::Undefined => "undefined",
+        Bson::Symbol(_) => "symbol",
+        Bson::DbPointer(_) => "dbPointer",
+    }
+}
+
+fn matches_bson_type(value: &Bson, expected: &str) -> bool {
+    let actual = bson_type_name(value);
+    match expected {
+        "number" => matches!(actual, "double" | "int" | "long" | "decimal"),
+        _ => actual == expected,
+    }
+}
+
+fn matches_json_type(value: &Bson, expected: &str) -> bool {
+    match expected {
+        "object" => matches!(value, Bson::Document(_)),
+        "array" => matches!(value, Bson::Array(_)),
+        "string" => matches!(value, Bson::String(_)),
+        "boolean" => matches!(value, Bson::Boolean(_)),
+        "null" => matches!(value, Bson::Null),
+        "number" => as_f64(value).is_some(),
+        "integer" => matches!(value, Bson::Int32(_) | Bson::Int64(_)),
+        _ => false,
+    }
+}
+
+fn type_names(spec: &Bson) -> Vec<String> {
+    match spec {
+        Bson::String(name) => vec![name.clone()],
+        Bson::Array(names) => names
+            .iter()
+            .filter_map(|name| name.as_str().map(str::to_string))
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+fn as_f64(value: &Bson) -> Option<f64> {
+    match value {
+        Bson::Int32(v) => Some(*v as f64),
+        Bson::Int64(v) => Some(*v as f64),
+        Bson::Double(v) => Some(*v),
+        Bson::Decimal128(v) => v.to_string().parse().ok(),
+        _ => None,
+    }
+}
+
+fn as_usize(value: Option<&Bson>) -> Option<usize> {
+    value.and_then(as_f64).map(|v| v as usize)
+}
+
+fn check_schema(schema: &Document, value: &Bson, path: &str, out: &mut Vec<Violation>) {
+    let mut fail = |message: String| {
+        out.push(Violation {
+            path: path.to_string(),
+            message,
+        })
+    };
+
+    if let Some(spec) = schema.get("bsonType") {
+        let names = type_names(spec);
+        if !names.iter().any(|name| matches_bson_type(value, name)) {
+            fail(format!(
+                "expected bsonType {}, found {}",
+                names.join(" | "),
+                bson_type_name(value)
+            ));
+            return;
+        }
+    }
+    if let Some(spec) = schema.get("type") {
+        let names = type_names(spec);
+        if !names.iter().any(|name| matches_json_type(value, name)) {
+            fail(format!(
+                "expected type {}, found {}",
+                names.join(" | "),
+                bson_type_name(value)
+            ));
+            return;
+        }
+    }
+    if schema
+        .get_array("enum")
+        .is_ok_and(|allowed| !allowed.contains(value))
+    {
+        fail(format!("{value} is not one of the allowed values"));
+    }
+
+    if let Some(number) = as_f64(value) {
+        let exclusive_min = schema.get_bool("exclusiveMinimum").unwrap_or(false);
+        let exclusive_max = schema.get_bool("exclusiveMaximum").unwrap_or(false);
+        if let Some(minimum) = schema
+            .get("minimum")
+            .and_then(as_f64)
+            .filter(|minimum| number < *minimum || (exclusive_min && number == *minimum))
+        {
+            fail(format!("{number} is below the minimum of {minimum}"));
+        }
+        if let Some(maximum) = schema
+            .get("maximum")
+            .and_then(as_f64)
+            .filter(|maximum| number > *maximum || (exclusive_max && number == *maximum))
+        {
+            fail(format!("{number} is above the maximum of {maximum}"));
+        }
+    }
+
+    if let Bson::String(text) = value {
+        let length = text.chars().count();
+        if let Some(min) = as_usize(schema.get("minLength")).filter(|min| length < *min) {
+            fail(format!("string is shorter than {min} characters"));
+        }
+        if let Some(max) = as_usize(schema.get("maxLength")).filter(|max| length > *max) {
+            fail(format!("string is longer than {max} characters"));
+        }
+    }
+
+    if let Bson::Array(items) = value {
+        if let Some(min) = as_usize(schema.get("minItems")).filter(|min| items.len() < *min) {
+            fail(format!("array has fewer than {min} items"));
+        }
+        if let Some(max) = as_usize(schema.get("maxItems")).filter(|max| items.len() > *max) {
+            fail(format!("array has more than {max} items"));
+        }
+        if schema.get_bool("uniqueItems").unwrap_or(false)
+            && items
+                .iter()
+                .enumerate()
+                .any(|(index, item)| items[..index].contains(item))
+        {
+            fail("array items are not unique".to_string());
+        }
+    }
+
+    if let Bson::Document(doc) = value {
+        if let Ok(required) = schema.get_array("required") {
+            for key in required.iter().filter_map(Bson::as_str) {
+                if !doc.contains_key(key) {
+                    out.push(Violation {
+                        path: child_path(path, key),
+                        message: "required field is missing".to_string(),
+                    });
+                }
+            }
+        }
+        if let Some(min) = as_usize(schema.get("minProperties")).filter(|min| doc.len() < *min) {
+            out.push(Violation {
+                path: path.to_string(),
+                message: format!("object has fewer than {min} fields"),
+            });
+        }
+        if let Some(max) = as_usize(schema.get("maxProperties")).filter(|max| doc.len() > *max) {
+            out.push(Violation {
+                path: path.to_string(),
+                message: format!("object has more than {max} fields"),
+            });
+        }
+
+        let properties = schema.get_document("properties").ok();
+        for (key, child) in doc {
+            match properties.and_then(|properties| properties.get_document(key).ok()) {
+                Some(child_schema) => {
+                    check_schema(child_schema, child, &child_path(path, key), out)
+                }
+                None => match schema.get("additionalProperties") {
+                    Some(Bson::Boolean(false)) => out.push(Violation {
+                        path: child_path(path, key),
+                        message: "field is not allowed by additionalProperties".to_string(),
+                    }),
+                    Some(Bson::Document(extra)) => {
+                        check_schema(extra, child, &child_path(path, key), out)
+                    }
+                    _ => {}
+                },
+            }
+        }
+    }
+
+    if let Bson::Array(items) = value {
+        match schema.get("items") {
+            Some(Bson::Document(item_schema)) => {
+                for (index, item) in items.iter().enumerate() {
+                    check_schema(
+                        item_schema,
+                        item,
+                        &child_path(path, &index.to_string()),
+                        out,
+                    );
+                }
+            }
+            Some(Bson::Array(item_schemas)) => {
+                for (index, (item, item_schema)) in items.iter().zip(item_schemas).enumerate() {
+                    if let Bson::Document(item_schema) = item_schema {
+                        check_schema(
+                            item_schema,
+                            item,
+                            &child_path(path, &index.to_string()),
+                            out,
+                        );
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    let subschema_failures = |schemas: &[Bson]| -> Vec<usize> {
+        schemas
+            .iter()
+            .filter_map(|schema| schema.as_document())
+            .map(|schema| {
+                let mut nested = Vec::new();
+                check_schema(schema, value, path, &mut nested);
+                nested.len()
+            })
+            .collect()
+    };
+    if let Ok(schemas) = schema.get_array("allOf") {
+        for schema in schemas.iter().filter_map(Bson::as_document) {
+            check_schema(schema, value, path, out);
+        }
+    }
+    if schema
+        .get_array("anyOf")
+        .is_ok_and(|schemas| !subschema_failures(schemas).contains(&0))
+    {
+        out.push(Violation {
+            path: path.to_string(),
+            message: "value does not match any schema in anyOf".to_string(),
+        });
+    }
+    if let Ok(schemas) = schema.get_array("oneOf") {
+        let matching = subschema_failures(schemas)
+            .into_iter()
+            .filter(|failures| *failures == 0)
+            .count();
+        if matching != 1 {
+            out.push(Violation {
+                path: path.to_string(),
+                message: format!("value matches {matching} schemas in oneOf, expected exactly 1"),
+            });
+        }
+    }
+    if let Ok(not_schema) = schema.get_document("not") {
+        let mut nested = Vec::new();
+        check_schema(not_schema, value, path, &mut nested);
+        if nested.is_empty() {
+            out.push(Violation {
+                path: path.to_string(),
+                message: "value must not match the schema in not".to_string(),
+            });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Validation, check_validation_rules, check_write, validate_document};
+    use mongodb::bson::doc;
+
+    #[test]
+    fn reports_each_violation_with_its_path() {
+        let validator = doc! {
+            "$jsonSchema": {
+                "bsonType": "object",
+                "required": ["name", "age"],
+                "properties": {
+                    "age": { "bsonType": "int", "minimum": 0 },
+                    "address": {
+                        "bsonType": "object",
+                        "properties": { "zip": { "bsonType": "string", "maxLength": 5 } }
+                    },
+                    "status": { "enum": ["active", "inactive"] }
+                }
+            }
+        };
+        let document = doc! {
+            "age": -1,
+            "address": { "zip": "1234567" },
+            "status": "deleted"
+        };
+
+        let violations: Vec<String> = validate_document(&validator, &document)
+            .iter()
+            .map(ToString::to_string)
+            .collect();
+
+        assert_eq!(
+            violations,
+            vec![
+                "name: required field is missing",
+                "age: -1 is below the minimum of 0",
+                "address.zip: string is longer than 5 characters",
+                "status: \"deleted\" is not one of the allowed values",
+            ]
+        );
+    }
+
+    #[test]
+    fn accepts_valid_documents_and_ignores_query_validators() {
+        let schema = doc! {
+            "$jsonSchema": {
+                "bsonType": "object",
+                "additionalProperties": false,
+                "properties": {
+                    "_id": {},
+                    "tags": { "bsonType": "array", "items": { "bsonType": "string" } }
+                }
+            }
+        };
+        assert!(validate_document(&schema, &doc! { "_id": 1, "tags": ["a"] }).is_empty());
+        assert_eq!(validate_document(&schema, &doc! { "extra": 1 }).len(), 1);
+        assert!(validate_document(&doc! { "age": { "$gte": 18 } }, &doc! { "age": 1 }).is_empty());
+    }
+
+    #[test]
+    fn moderate_level_skips_documents_that_already_fail() {
+        let validation = Validation {
+            validator: doc! { "$jsonSchema": { "required": ["name"] } },
+            level: "moderate".to_string(),
+            action: "warn".to_string(),
+        };
+        let invalid = doc! { "_id": 1 };
+        let valid = doc! { "_id": 1, "name": "a" };
+
+        let update = check_write(&validation, &doc! { "_id": 1, "age": 2 }, Some(&invalid));
+        assert!(update.violations.is_empty());
+        let update = check_write(&validation, &invalid, Some(&valid));
+        assert_eq!(update.violations.len(), 1);
+        assert!(update.warns_only);
+        assert_eq!(check_write(&validation, &invalid, None).violations.len(), 1);
+    }
+
+    #[test]
+    fn checks_edited_validation_rules() {
+        let rules =
+            doc! { "validator": {}, "validationLevel": "moderate", "validationAction": "warn" };
+        assert!(check_validation_rules(&rules).is_ok());
+        assert!(check_validation_rules(&doc! { "validationLevel": "loose" }).is_err());
+        assert!(check_validation_rules(&doc! { "validator": "x" }).is_err());
+        assert!(check_validation_rules(&doc! { "validatior": {} }).is_err());
+    }
+}
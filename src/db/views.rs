@@ -0,0 +1,85 @@
+use futures::stream::TryStreamExt;
+use mongodb::{
+    Client,
+    bson::{Document, doc},
+    options::CreateCollectionOptions,
+    results::CollectionType,
+};
+use std::collections::HashMap;
+
+pub struct ViewInfo {
+    pub view_on: String,
+    pub pipeline: Vec<Document>,
+}
+
+/// Lists the views in a database keyed by name. `listCollectionNames` does
+/// not tell views apart, so the tree looks them up here.
+pub async fn list_views(
+    client: &Client,
+    db_name: &str,
+) -> Result<HashMap<String, ViewInfo>, mongodb::error::Error> {
+    let specs: Vec<_> = client
+        .database(db_name)
+        .list_collections()
+        .filter(doc! { "type": "view" })
+        .await?
+        .try_collect()
+        .await?;
+
+    Ok(specs
+        .into_iter()
+        .filter(|spec| matches!(spec.collection_type, CollectionType::View))
+        .map(|spec| {
+            let info = ViewInfo {
+                view_on: spec.options.view_on.unwrap_or_default(),
+                pipeline: spec.options.pipeline.unwrap_or_default(),
+            };
+            (spec.name, info)
+        })
+        .collect())
+}
+
+/// Turns a find-style filter, sort and projection into view pipeline stages,
+/// skipping the empty ones.
+pub fn find_pipeline(filter: Document, sort: Document, projection: Document) -> Vec<Document> {
+    [
+        ("$match", filter),
+        ("$sort", sort),
+        ("$project", projection),
+    ]
+    .into_iter()
+    .filter(|(_, stage)| !stage.is_empty())
+    .map(|(name, stage)| doc! { name: stage })
+    .collect()
+}
+
+pub async fn create_view(
+    client: &Client,
+    db_name: &str,
+    name: &str,
+    view_on: &str,
+    pipeline: Vec<Document>,
+) -> Result<(), mongodb::error::Error> {
+    let mut options = CreateCollectionOptions::default();
+    options.view_on = Some(view_on.to_string());
+    options.pipeline = Some(pipeline);
+    super::client::create_collection(client, db_name, name, options).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::find_pipeline;
+    use mongodb::bson::doc;
+
+    #[test]
+    fn builds_stages_for_non_empty_parts() {
+        let pipeline = find_pipeline(doc! { "a": 1 }, doc! {}, doc! { "b": 0 });
+        assert_eq!(
+            pipeline,
+            vec![
+                doc! { "$match": { "a": 1 } },
+                doc! { "$project": { "b": 0 } }
+            ]
+        );
+    }
+}
@@ -1,5 +1,7 @@
-use crate::app::{AppState, PendingBulkUpdate};
+use crate::app::{AppState, PendingBulkUpdate, PendingValidation, WarnedWrite};
 use crate::db::client::{apply_edited_json, count_documents_with_filter};
+use crate::db::staging::StagedChange;
+use crate::db::validation::{check_document, check_validation_rules, fetch_validation_rules};
 use crossterm::{
     cursor::{Hide, Show},
     event::{DisableMouseCapture, EnableMouseCapture},
@@ -7,7 +9,7 @@ use crossterm::{
     terminal::{EnterAlternateScreen, LeaveAlternateScreen, disable_raw_mode, enable_raw_mode},
 };
 use edit::edit;
-use mongodb::bson::Document;
+use mongodb::{bson::Document, options::UpdateModifications};
 use std::io::{self, Stdout, Write};
 
 const BULK_UPDATE_TEMPLATE: &str = r#"{
@@ -16,6 +18,49 @@ const BULK_UPDATE_TEMPLATE: &str = r#"{
   }
 }"#;
 
+const PIPELINE_UPDATE_STAGES: [&str; 6] = [
+    "$addFields",
+    "$set",
+    "$project",
+    "$unset",
+    "$replaceRoot",
+    "$replaceWith",
+];
+
+/// Parses the bulk update editor contents as either an operator document
+/// (`{"$set": ...}`) or an update pipeline (`[{"$set": ...}, ...]`).
+fn parse_bulk_update(text: &str) -> Result<UpdateModifications, String> {
+    if text.trim_start().starts_with('[') {
+        let pipeline: Vec<Document> = serde_json::from_str(text)
+            .map_err(|error| format!("Pipeline is not valid JSON: {error}"))?;
+        if pipeline.is_empty() {
+            return Err("Update pipeline must contain at least one stage.".to_string());
+        }
+        for stage in &pipeline {
+            let mut keys = stage.keys();
+            match (keys.next(), keys.next()) {
+                (Some(name), None) if PIPELINE_UPDATE_STAGES.contains(&name.as_str()) => {}
+                _ => {
+                    return Err(format!(
+                        "Each pipeline stage must be one of {}.",
+                        PIPELINE_UPDATE_STAGES.join(", ")
+                    ));
+                }
+            }
+        }
+        return Ok(UpdateModifications::Pipeline(pipeline));
+    }
+
+    let update: Document =
+        serde_json::from_str(text).map_err(|error| format!("Update is not valid JSON: {error}"))?;
+    if update.is_empty() || update.keys().any(|key| !key.starts_with('$')) {
+        return Err(
+            "Update must contain MongoDB operators such as $set, $unset, or $inc.".to_string(),
+        );
+    }
+    Ok(UpdateModifications::Document(update))
+}
+
 pub async fn open_bulk_update_editor(
     state: &mut AppState,
 ) -> Result<Option<PendingBulkUpdate>, String> {
@@ -47,13 +92,7 @@ pub async fn open_bulk_update_editor(
         return Ok(None);
     }
 
-    let update: Document = serde_json::from_str(&edited)
-        .map_err(|error| format!("Update is not valid JSON: {error}"))?;
-    if update.is_empty() || update.keys().any(|key| !key.starts_with('$')) {
-        return Err(
-            "Update must contain MongoDB operators such as $set, $unset, or $inc.".to_string(),
-        );
-    }
+    let update = parse_bulk_update(&edited)?;
 
     let matched_count = count_documents_with_filter(&client, &db, &collection, filter.clone())
         .await
@@ -65,9 +104,103 @@ pub async fn open_bulk_update_editor(
         filter,
         update,
         matched_count,
+        conversion: None,
+    }))
+}
+
+const INDEX_SPEC_TEMPLATE: &str = r#"{
+  "key": {
+    "field": 1
+  },
+  "unique": false
+}"#;
+
+/// Opens a `createIndexes` specification (`key`, `name` and any index
+/// options) in the external editor.
+pub fn open_index_spec_editor(state: &mut AppState) -> Result<Option<Document>, String> {
+    let edited = {
+        let _guard =
+            TuiSuspendGuard::suspend().map_err(|e| format!("Could not suspend TUI: {e}"))?;
+        edit(INDEX_SPEC_TEMPLATE).map_err(|e| format!("Could not open external editor: {e}"))?
+    };
+    state.redraw = true;
+
+    if edited.trim().is_empty() || edited.trim() == INDEX_SPEC_TEMPLATE.trim() {
+        return Ok(None);
+    }
+
+    let spec: Document = serde_json::from_str(&edited)
+        .map_err(|e| format!("Index specification is not a valid JSON object: {e}"))?;
+    if spec.get_document("key").map_or(true, |key| key.is_empty()) {
+        return Err("Index specification needs a non-empty \"key\" object.".to_string());
+    }
+    Ok(Some(spec))
+}
+
+pub async fn open_validation_editor(
+    state: &mut AppState,
+    db: &str,
+    collection: &str,
+) -> Result<Option<PendingValidation>, String> {
+    let client = state
+        .mongo_client
+        .clone()
+        .ok_or_else(|| "No MongoDB connection.".to_string())?;
+    let rules = fetch_validation_rules(&client, db, collection)
+        .await
+        .map_err(|e| format!("Could not load validation rules: {e}"))?;
+    let initial = serde_json::to_string_pretty(&rules)
+        .map_err(|e| format!("Validation rules could not be converted to JSON: {e}"))?;
+
+    let edited = {
+        let _guard =
+            TuiSuspendGuard::suspend().map_err(|e| format!("Could not suspend TUI: {e}"))?;
+        edit(&initial).map_err(|e| format!("Could not open external editor: {e}"))?
+    };
+    state.redraw = true;
+
+    if edited.trim().is_empty() || edited.trim() == initial.trim() {
+        return Ok(None);
+    }
+
+    let rules: Document = serde_json::from_str(&edited)
+        .map_err(|e| format!("Validation rules are not a valid JSON object: {e}"))?;
+    check_validation_rules(&rules)?;
+
+    Ok(Some(PendingValidation {
+        db: db.to_string(),
+        collection: collection.to_string(),
+        rules,
     }))
 }
 
+pub fn open_clone_editor(state: &mut AppState) -> Result<Option<Document>, String> {
+    let mut copy = state
+        .current_documents
+        .get(state.selected_doc_index)
+        .cloned()
+        .ok_or_else(|| "No document selected.".to_string())?;
+    copy.remove("_id");
+
+    let initial = serde_json::to_string_pretty(&copy)
+        .map_err(|e| format!("Document could not be converted to JSON: {e}"))?;
+
+    let edited = {
+        let _guard =
+            TuiSuspendGuard::suspend().map_err(|e| format!("Could not suspend TUI: {e}"))?;
+        edit(&initial).map_err(|e| format!("Could not open external editor: {e}"))?
+    };
+    state.redraw = true;
+
+    if edited.trim().is_empty() {
+        return Ok(None);
+    }
+
+    serde_json::from_str::<Document>(&edited)
+        .map(Some)
+        .map_err(|e| format!("Clone is not a valid JSON object: {e}"))
+}
+
 pub async fn open_in_external_editor(state: &mut AppState) -> Result<(), String> {
     let (db_name, col_name) = state
         .selected_collection
@@ -97,6 +230,55 @@ pub async fn open_in_external_editor(state: &mut AppState) -> Result<(), String>
         return Ok(());
     }
 
+    let check = match serde_json::from_str::<Document>(&edited) {
+        Ok(edited_doc) => {
+            check_document(client, &db_name, &col_name, &edited_doc, Some(original_doc))
+                .await
+                .map_err(|e| format!("Could not load collection validator: {e}"))?
+        }
+        Err(_) => Default::default(),
+    };
+    if !check.violations.is_empty() {
+        let violations = check.violations.iter().map(ToString::to_string).collect();
+        if !check.warns_only {
+            state.show_violations("Validation failed · edit not saved", violations);
+            state.redraw = true;
+            return Ok(());
+        }
+        let mut edited_doc = serde_json::from_str::<Document>(&edited)
+            .map_err(|e| format!("Edited document is not a valid JSON object: {e}"))?;
+        if let Some(id) = original_id {
+            edited_doc.insert("_id", id);
+        }
+        let write = WarnedWrite::Update {
+            db: db_name,
+            collection: col_name,
+            original: original_doc.clone(),
+            edited: edited_doc,
+        };
+        state.show_violations("Validator warning · edit not saved yet", violations);
+        state.confirm_warned_write(write);
+        state.redraw = true;
+        return Ok(());
+    }
+
+    if state.staged_changes.is_some() {
+        let mut edited_doc = serde_json::from_str::<Document>(&edited)
+            .map_err(|e| format!("Edited document is not a valid JSON object: {e}"))?;
+        if let Some(id) = original_id {
+            edited_doc.insert("_id", id);
+        }
+        let change = StagedChange::Update {
+            db: db_name,
+            collection: col_name,
+            original: original_doc.clone(),
+            edited: edited_doc,
+        };
+        state.stage(change);
+        state.redraw = true;
+        return Ok(());
+    }
+
     if let Err(e) = apply_edited_json(client, &db_name, &col_name, original_doc, &edited).await {
         state.popup_message = Some(format!("❌ Database update failed: {e}"));
         state.redraw = true;
@@ -147,3 +329,28 @@ impl Drop for TuiSuspendGuard {
         let _ = self.stdout.flush();
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::parse_bulk_update;
+    use mongodb::options::UpdateModifications;
+
+    #[test]
+    fn accepts_operator_documents_and_pipelines() {
+        assert!(matches!(
+            parse_bulk_update(r#"{"$set": {"a": 1}}"#),
+            Ok(UpdateModifications::Document(_))
+        ));
+        assert!(matches!(
+            parse_bulk_update(r#"[{"$set": {"total": {"$add": ["$a", "$b"]}}}]"#),
+            Ok(UpdateModifications::Pipeline(stages)) if stages.len() == 1
+        ));
+    }
+
+    #[test]
+    fn rejects_replacements_and_unknown_stages() {
+        assert!(parse_bulk_update(r#"{"a": 1}"#).is_err());
+        assert!(parse_bulk_update(r#"[]"#).is_err());
+        assert!(parse_bulk_update(r#"[{"$match": {"a": 1}}]"#).is_err());
+    }
+}
@@ -104,6 +104,29 @@ pub async fn handle_insert(key: KeyEvent, state: &mut AppState) -> bool {
                         }
                     }
                 }
+                InputContext::DatabaseName => {
+                    let Some(SelectableItem::Database { uri, name }) =
+                        state.tree_items.get(state.selected_index)
+                    else {
+                        state.popup_message = Some("❗ No database selected.".to_string());
+                        return false;
+                    };
+                    let new_name = state.input_text.trim();
+                    if new_name.is_empty() || new_name == name {
+                        state.popup_message =
+                            Some("❗ New database name is empty or unchanged.".to_string());
+                        return false;
+                    }
+
+                    let rename = crate::app::PendingDatabaseRename {
+                        uri: uri.clone(),
+                        db: name.clone(),
+                        new_name: new_name.to_string(),
+                    };
+                    state.popup_message_success = None;
+                    state.popup_message = Some(rename.confirmation_message());
+                    state.pending_db_rename = Some(rename);
+                }
                 InputContext::SearchCollections => {
                     let q = state.input_text.trim().to_lowercase();
                     state.collection_search_hits.clear();
@@ -135,6 +158,27 @@ pub async fn handle_insert(key: KeyEvent, state: &mut AppState) -> bool {
                     }
                 }
 
+                InputContext::CloneTargetCollection => {
+                    let collection = state.input_text.trim().to_string();
+                    let Some(db) = state
+                        .pending_clone
+                        .as_ref()
+                        .and_then(|clone| clone.target_db.clone())
+                    else {
+                        state.popup_message = Some("❗ No clone is pending.".to_string());
+                        return false;
+                    };
+                    if collection.is_empty() {
+                        state.popup_message =
+                            Some("❗ Target collection name is empty.".to_string());
+                        return false;
+                    }
+
+                    crate::keybindings::normal::insert_clone(state, &db, &collection).await;
+                    state.collection_to_load =
+                        state.connected_uri.clone().map(|uri| (uri, db.clone()));
+                }
+
                 InputContext::None => {
                     if state.active_input.is_some() {
                         if let Some((uri, db, name)) = &state.selected_collection {
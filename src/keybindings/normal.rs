@@ -1,11 +1,31 @@
 use crate::app::{
-    ActiveInputField, AppMode, AppState, FocusArea, InputContext, PendingBulkDeletion,
-    PendingDeletion, SelectableItem,
+    ActiveInputField, AppMode, AppState, FocusArea, InfoPanel, InputContext, PanelKind, PanelLine,
+    PanelTone, PendingBulkDeletion, PendingClone, PendingCopy, PendingDeletion, SelectableItem,
+    WarnedWrite,
+};
+use crate::db::preview::{
+    CONVERSION_FAILURE_LIMIT, conversion_failures, diff_documents, preview_update,
+};
+use crate::db::rename::RenameJob;
+use crate::db::staging::{StagedChange, commit_staged_changes};
+use crate::db::validation::{
+    VIOLATOR_LIMIT, apply_validation_rules, find_violators, validate_document,
+};
+use crate::keybindings::editor::{
+    open_bulk_update_editor, open_clone_editor, open_in_external_editor, open_validation_editor,
 };
-use crate::keybindings::editor::{open_bulk_update_editor, open_in_external_editor};
 use crate::tui::events::{goto_collection, inner_end_pos};
 use crate::tui::filepicker::{FilePickerMode, FilePickerState};
+use crate::tui::form::{FormField, FormPurpose, FormState};
+use crate::tui::index_panel::{open_index_panel, open_index_report};
+use crate::tui::ops_panel::open_ops_panel;
+use crate::tui::panel_events::{close_info_panel, handle_panel_key};
+use crate::tui::profiler_panel::open_profiler_panel;
+use crate::tui::replset_panel::open_replset_panel;
+use crate::tui::stats_panel::open_stats_panel;
+use crate::tui::users_panel::open_users_panel;
 use crate::utils::write_clipboard_string;
+use crate::value_type::{ValueType, editable_text};
 use crate::widgets::help_popup::HELP_TEXT;
 use bson::{Bson, Document};
 use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
@@ -80,6 +100,36 @@ async fn confirm_deletion(state: &mut AppState) {
                 }
             }
         }
+        PendingDeletion::Index {
+            db,
+            collection,
+            name,
+        } => match crate::db::indexes::drop_index(&client, &db, &collection, &name).await {
+            Ok(_) => {
+                open_index_panel(state, &db, &collection).await;
+                state.popup_message_success = Some(format!("✅ Dropped index: {name}"));
+            }
+            Err(error) => state.popup_message = Some(format!("❌ Failed to drop index: {error}")),
+        },
+        PendingDeletion::AllDocuments { db, collection } => {
+            match crate::db::truncate::TruncateJob::new(&client, &db, &collection).await {
+                Ok(job) => state.truncate_job = Some(job),
+                Err(error) => {
+                    state.popup_message = Some(format!("❌ Could not empty collection: {error}"))
+                }
+            }
+        }
+        PendingDeletion::User { db, name } => {
+            match crate::db::users::drop_user(&client, &db, &name).await {
+                Ok(()) => {
+                    open_users_panel(state, &db).await;
+                    state.popup_message_success = Some(format!("✅ Dropped user: {name}"));
+                }
+                Err(error) => {
+                    state.popup_message = Some(format!("❌ Failed to drop user: {error}"))
+                }
+            }
+        }
     }
 }
 
@@ -93,6 +143,7 @@ async fn confirm_bulk_update(state: &mut AppState) {
     };
 
     state.popup_message = None;
+    state.info_panel = None;
     match crate::db::client::update_documents_with_filter(
         &client,
         &update.db,
@@ -110,6 +161,671 @@ async fn confirm_bulk_update(state: &mut AppState) {
     }
 }
 
+fn push_diff_lines(lines: &mut Vec<PanelLine>, before: &Document, after: &Document) {
+    let changes = diff_documents(before, after);
+    if changes.is_empty() {
+        lines.push(PanelLine::new(PanelTone::Muted, "  (no changes)"));
+    }
+    for change in changes {
+        if let Some(value) = change.before {
+            lines.push(PanelLine::new(
+                PanelTone::Removed,
+                format!("- {}: {value}", change.path),
+            ));
+        }
+        if let Some(value) = change.after {
+            lines.push(PanelLine::new(
+                PanelTone::Added,
+                format!("+ {}: {value}", change.path),
+            ));
+        }
+    }
+}
+
+async fn preview_bulk_update(state: &mut AppState) {
+    let Some(update) = &state.pending_bulk_update else {
+        return;
+    };
+    let Some(client) = state.mongo_client.clone() else {
+        state.popup_message = Some("❌ No active MongoDB connection.".to_string());
+        return;
+    };
+
+    let samples = match preview_update(
+        &client,
+        &update.db,
+        &update.collection,
+        &update.filter,
+        &update.update,
+    )
+    .await
+    {
+        Ok(samples) => samples,
+        Err(error) => {
+            state.popup_message = Some(format!("❌ Could not load preview sample: {error}"));
+            return;
+        }
+    };
+
+    let mut lines = Vec::new();
+    for (index, (before, after)) in samples.iter().enumerate() {
+        let id = before
+            .get("_id")
+            .map(|id| id.to_string())
+            .unwrap_or_else(|| "?".to_string());
+        lines.push(PanelLine::new(
+            PanelTone::Header,
+            format!("Document {} (_id: {id}):", index + 1),
+        ));
+
+        match after {
+            Ok(after) => push_diff_lines(&mut lines, before, after),
+            Err(error) => lines.push(PanelLine::new(PanelTone::Removed, format!("  ⚠️ {error}"))),
+        }
+        lines.push(PanelLine::new(PanelTone::Normal, ""));
+    }
+
+    if samples.is_empty() {
+        lines.push(PanelLine::new(
+            PanelTone::Muted,
+            "No documents match the current filter.",
+        ));
+    }
+
+    if let Some((path, to)) = &update.conversion {
+        match conversion_failures(
+            &client,
+            &update.db,
+            &update.collection,
+            &update.filter,
+            path,
+            to,
+        )
+        .await
+        {
+            Ok(failures) if failures.is_empty() => lines.push(PanelLine::new(
+                PanelTone::Added,
+                format!("Every '{path}' value converts to {to}."),
+            )),
+            Ok(failures) => {
+                lines.push(PanelLine::new(
+                    PanelTone::Header,
+                    format!(
+                        "Values that cannot be converted to {to} (kept unchanged, first {CONVERSION_FAILURE_LIMIT}):"
+                    ),
+                ));
+                for failure in failures {
+                    let id = failure.get("_id").map(ToString::to_string);
+                    let value = failure.get("value").map(ToString::to_string);
+                    lines.push(PanelLine::new(
+                        PanelTone::Removed,
+                        format!(
+                            "  _id {}: {}",
+                            id.unwrap_or_default(),
+                            value.unwrap_or_else(|| "(missing)".to_string())
+                        ),
+                    ));
+                }
+            }
+            Err(error) => lines.push(PanelLine::new(
+                PanelTone::Removed,
+                format!("⚠️ Could not check conversions: {error}"),
+            )),
+        }
+    }
+
+    state.popup_message = None;
+    state.info_panel = Some(InfoPanel::new(
+        format!(
+            "Update preview: {} of {} document(s) · y: apply · n: cancel · Esc: close",
+            samples.len(),
+            update.matched_count
+        ),
+        lines,
+    ));
+}
+
+/// Lists existing documents that the pending validator would reject.
+async fn preview_violators(state: &mut AppState) {
+    let Some(validation) = &state.pending_validation else {
+        return;
+    };
+    let Some(client) = state.mongo_client.clone() else {
+        state.popup_message = Some("❌ No active MongoDB connection.".to_string());
+        return;
+    };
+    let validator = validation
+        .rules
+        .get_document("validator")
+        .cloned()
+        .unwrap_or_default();
+    if validator.is_empty() {
+        state.popup_message = Some("No validator set: every document passes.".to_string());
+        return;
+    }
+
+    let (count, documents) = match find_violators(
+        &client,
+        &validation.db,
+        &validation.collection,
+        &validator,
+        VIOLATOR_LIMIT,
+    )
+    .await
+    {
+        Ok(result) => result,
+        Err(error) => {
+            state.popup_message = Some(format!("❌ Could not check documents: {error}"));
+            return;
+        }
+    };
+
+    let mut lines = Vec::new();
+    if documents.is_empty() {
+        lines.push(PanelLine::new(
+            PanelTone::Added,
+            "Every existing document passes the new validator.",
+        ));
+    }
+    for document in &documents {
+        let id = document
+            .get("_id")
+            .map(ToString::to_string)
+            .unwrap_or_else(|| "?".to_string());
+        lines.push(PanelLine::new(PanelTone::Header, format!("_id: {id}")));
+        for violation in validate_document(&validator, document) {
+            lines.push(PanelLine::new(PanelTone::Removed, format!("  {violation}")));
+        }
+    }
+
+    let title = format!(
+        "Violators: {count} document(s) fail the validator (first {VIOLATOR_LIMIT} shown) · y: apply · n: cancel · Esc: close"
+    );
+    state.popup_message = None;
+    state.info_panel = Some(InfoPanel::new(title, lines));
+}
+
+async fn confirm_validation(state: &mut AppState) {
+    let Some(validation) = state.pending_validation.take() else {
+        return;
+    };
+    let Some(client) = state.mongo_client.clone() else {
+        state.popup_message = Some("❌ No active MongoDB connection.".to_string());
+        return;
+    };
+
+    state.info_panel = None;
+    state.popup_message = None;
+    match apply_validation_rules(
+        &client,
+        &validation.db,
+        &validation.collection,
+        validation.rules,
+    )
+    .await
+    {
+        Ok(()) => {
+            state.popup_message_success = Some(format!(
+                "✅ Updated validation rules of {}.{}",
+                validation.db, validation.collection
+            ))
+        }
+        Err(error) => {
+            state.popup_message = Some(format!("❌ Failed to update validation rules: {error}"))
+        }
+    }
+}
+
+/// Sends a write the validator only warns about.
+async fn confirm_warned_write(state: &mut AppState) {
+    let Some(write) = state.pending_warned_write.take() else {
+        return;
+    };
+    let Some(client) = state.mongo_client.clone() else {
+        state.popup_message = Some("❌ No active MongoDB connection.".to_string());
+        return;
+    };
+
+    close_info_panel(state);
+    state.popup_message = None;
+    match write {
+        WarnedWrite::Update {
+            db,
+            collection,
+            original,
+            edited,
+        } => {
+            if state.staged_changes.is_some() {
+                state.stage(StagedChange::Update {
+                    db,
+                    collection,
+                    original,
+                    edited,
+                });
+                return;
+            }
+            match crate::db::client::apply_edited_document(
+                &client,
+                &db,
+                &collection,
+                &original,
+                &edited,
+            )
+            .await
+            {
+                Ok(()) => {
+                    if let Some(slot) = state
+                        .current_documents
+                        .iter_mut()
+                        .find(|doc| doc.get("_id") == edited.get("_id"))
+                    {
+                        *slot = edited;
+                    }
+                    state.popup_message_success =
+                        Some("✅ Document updated despite validator warnings.".to_string());
+                }
+                Err(error) => {
+                    state.popup_message = Some(format!("❌ Database update failed: {error}"))
+                }
+            }
+        }
+        WarnedWrite::Insert {
+            db,
+            collection,
+            document,
+        } => {
+            if state.staged_changes.is_some() {
+                state.stage(StagedChange::Insert {
+                    db,
+                    collection,
+                    document,
+                });
+                return;
+            }
+            match crate::db::client::insert_document(&client, &db, &collection, document).await {
+                Ok(id) => {
+                    state.reload_documents_for_selected_collection().await;
+                    state.popup_message_success = Some(format!(
+                        "✅ Inserted into {db}.{collection} despite validator warnings (_id: {id})"
+                    ));
+                }
+                Err(error) => {
+                    state.popup_message = Some(format!("❌ Failed to insert document: {error}"))
+                }
+            }
+        }
+        WarnedWrite::Import { db, batches } => {
+            let mut imported = 0;
+            for (collection, documents) in batches {
+                match crate::db::import_export::insert_documents(
+                    &client,
+                    &db,
+                    &collection,
+                    documents,
+                )
+                .await
+                {
+                    Ok(count) => imported += count,
+                    Err(error) => {
+                        state.popup_message =
+                            Some(format!("❌ Import into {db}.{collection} failed: {error}"));
+                        return;
+                    }
+                }
+            }
+            if let Some(uri) = state.connected_uri.clone() {
+                state.collection_to_load = Some((uri, db.clone()));
+            }
+            state.popup_message_success = Some(format!(
+                "📥 Imported {imported} document(s) into {db} despite validator warnings"
+            ));
+        }
+    }
+}
+
+async fn confirm_kill_op(state: &mut AppState) {
+    let Some(kill) = state.pending_kill_op.take() else {
+        return;
+    };
+    let Some(client) = state.mongo_client.clone() else {
+        state.popup_message = Some("❌ No active MongoDB connection.".to_string());
+        return;
+    };
+
+    state.popup_message = None;
+    match crate::db::ops::kill_op(&client, kill.opid.clone()).await {
+        Ok(()) => {
+            if let Some(InfoPanel {
+                kind: PanelKind::Operations { filter, paused, .. },
+                ..
+            }) = &state.info_panel
+            {
+                let (filter, paused) = (filter.clone(), *paused);
+                open_ops_panel(state, filter, paused).await;
+            }
+            state.popup_message_success = Some(format!("✅ Killed operation {}", kill.opid));
+        }
+        Err(error) => state.popup_message = Some(format!("❌ Failed to kill operation: {error}")),
+    }
+}
+
+async fn start_database_rename(state: &mut AppState) {
+    let Some(rename) = state.pending_db_rename.take() else {
+        return;
+    };
+    let Some(client) = state.mongo_client.clone() else {
+        state.popup_message = Some("❌ No active MongoDB connection.".to_string());
+        return;
+    };
+
+    state.popup_message = None;
+    match RenameJob::new(client, &rename.uri, &rename.db, &rename.new_name).await {
+        Ok(job) => state.rename_job = Some(job),
+        Err(error) => state.popup_message = Some(format!("❌ Cannot rename database: {error}")),
+    }
+}
+
+async fn cancel_database_rename(state: &mut AppState) {
+    let Some(mut job) = state.rename_job.take() else {
+        return;
+    };
+    state.popup_message = Some(match job.rollback().await {
+        Ok(()) => format!("Rename cancelled; '{}' was restored.", job.source_db),
+        Err(error) => format!("❌ Rename cancelled but rollback failed: {error}"),
+    });
+    state.collection_to_load = Some((job.uri.clone(), job.source_db.clone()));
+}
+
+pub async fn insert_clone(state: &mut AppState, db: &str, collection: &str) {
+    let Some(clone) = state.pending_clone.take() else {
+        return;
+    };
+    let Some(client) = state.mongo_client.clone() else {
+        state.popup_message = Some("❌ No active MongoDB connection.".to_string());
+        return;
+    };
+
+    match crate::db::validation::check_document(&client, db, collection, &clone.document, None)
+        .await
+    {
+        Ok(check) if !check.violations.is_empty() => {
+            let violations = check.violations.iter().map(ToString::to_string).collect();
+            if !check.warns_only {
+                state.show_violations("Validation failed · clone not inserted", violations);
+                return;
+            }
+            state.show_violations("Validator warning · clone not inserted yet", violations);
+            state.confirm_warned_write(WarnedWrite::Insert {
+                db: db.to_string(),
+                collection: collection.to_string(),
+                document: clone.document,
+            });
+            return;
+        }
+        Ok(_) => {}
+        Err(error) => {
+            state.popup_message = Some(format!("❌ Could not load collection validator: {error}"));
+            return;
+        }
+    }
+
+    if state.staged_changes.is_some() {
+        state.stage(StagedChange::Insert {
+            db: db.to_string(),
+            collection: collection.to_string(),
+            document: clone.document,
+        });
+        return;
+    }
+
+    state.popup_message = None;
+    match crate::db::client::insert_document(&client, db, collection, clone.document).await {
+        Ok(id) => {
+            if state
+                .selected_collection
+                .as_ref()
+                .is_some_and(|(_, selected_db, selected)| {
+                    selected_db == db && selected == collection
+                })
+            {
+                state.reload_documents_for_selected_collection().await;
+            }
+            state.popup_message_success = Some(format!(
+                "✅ Cloned document into {db}.{collection} (_id: {id})"
+            ));
+        }
+        Err(error) => state.popup_message = Some(format!("❌ Failed to clone document: {error}")),
+    }
+}
+
+/// Whether the tree item or open collection a key acts on is listed under a
+/// connection other than the active one. Those actions run through
+/// `state.mongo_client`, so they would reach the wrong server.
+fn targets_other_connection(state: &AppState) -> bool {
+    let uri = match state.focus {
+        FocusArea::Connections => match state.tree_items.get(state.selected_index) {
+            Some(
+                SelectableItem::Uri { uri, .. }
+                | SelectableItem::Database { uri, .. }
+                | SelectableItem::Collection { uri, .. },
+            ) => Some(uri),
+            None => None,
+        },
+        FocusArea::Documents => state.selected_collection.as_ref().map(|(uri, _, _)| uri),
+    };
+    uri.is_some_and(|uri| state.connected_uri.as_ref() != Some(uri))
+}
+
+/// Guard for actions that go through `state.mongo_client`: refuses them with
+/// a message when `targets_other_connection`. Returns `true` when refused.
+fn refuse_other_connection(state: &mut AppState) -> bool {
+    if !targets_other_connection(state) {
+        return false;
+    }
+    state.popup_message = Some(
+        "❗ This item belongs to another connection; connect to it first (Enter).".to_string(),
+    );
+    true
+}
+
+async fn pick_clone_target(state: &mut AppState) {
+    // The clone is inserted through the active client.
+    if targets_other_connection(state) {
+        state.popup_message = Some("❗ Pick a target under the connected server.".to_string());
+        return;
+    }
+
+    match state.tree_items.get(state.selected_index) {
+        Some(SelectableItem::Collection { db, name, .. }) => {
+            let (db, name) = (db.clone(), name.clone());
+            insert_clone(state, &db, &name).await;
+        }
+        Some(SelectableItem::Database { name, .. }) => {
+            let db = name.clone();
+            if let Some(clone) = &mut state.pending_clone {
+                state.input_text = clone.collection.clone();
+                clone.target_db = Some(db);
+            }
+            state.mode = AppMode::Insert;
+            state.input_context = InputContext::CloneTargetCollection;
+            state.cursor_position = state.input_text.chars().count();
+        }
+        _ => {
+            state.popup_message =
+                Some("❗ Connect and pick a target database or collection.".to_string());
+        }
+    }
+}
+
+/// Opens the export options for a collection. The query shown in the
+/// Documents pane is filled in when it shows this collection.
+fn open_export_form(state: &mut AppState, uri: &str, db: &str, collection: &str) {
+    let showing = state.selected_collection.as_ref()
+        == Some(&(uri.to_string(), db.to_string(), collection.to_string()));
+    let (title, filter, sort) = if showing {
+        (
+            format!("Export {db}.{collection} as shown"),
+            state.filter_text.clone(),
+            state.sort_text.clone(),
+        )
+    } else {
+        (
+            format!("Export {db}.{collection}"),
+            "{}".to_string(),
+            "{}".to_string(),
+        )
+    };
+    let file = crate::utils::get_data_dir().join(format!("{db}_{collection}"));
+    state.form = Some(FormState::new(
+        title,
+        FormPurpose::ExportCollection {
+            db: db.to_string(),
+            collection: collection.to_string(),
+        },
+        vec![
+            FormField::choice("Format", &["json", "csv", "tsv"], "json"),
+            FormField::text("File", file.to_string_lossy()),
+            FormField::text("Limit", ""),
+            FormField::choice("Source", &["current query", "pipeline"], "current query"),
+            FormField::text("Filter", filter),
+            FormField::text("Sort", sort),
+            FormField::text("Projection", "{}"),
+            FormField::text("Pipeline", "[]"),
+            FormField::text("Fields", ""),
+            FormField::choice("Arrays", &["join", "index"], "join"),
+            FormField::choice("If file exists", &["ask", "overwrite"], "ask"),
+        ],
+    ));
+}
+
+/// Opens the copy options for the database, collection or connection
+/// selected in the tree as the target.
+fn pick_copy_target(state: &mut AppState) {
+    let Some(copy) = &state.pending_copy else {
+        return;
+    };
+    let (uri, db, collection) = match state.tree_items.get(state.selected_index) {
+        Some(SelectableItem::Uri { uri, .. }) => (uri.clone(), copy.db.clone(), None),
+        Some(SelectableItem::Database { uri, name }) => (uri.clone(), name.clone(), None),
+        Some(SelectableItem::Collection { uri, db, name }) => {
+            (uri.clone(), db.clone(), Some(name.clone()))
+        }
+        None => return,
+    };
+
+    let source = match &copy.collection {
+        Some(collection) => format!("{}.{collection}", copy.db),
+        None => copy.db.clone(),
+    };
+    let mut fields = vec![FormField::text("Target database", db)];
+    if let Some(source_collection) = &copy.collection {
+        fields.push(FormField::text(
+            "Target collection",
+            collection.unwrap_or_else(|| source_collection.clone()),
+        ));
+        fields.push(FormField::text("Filter", copy.filter.clone()));
+    }
+    fields.push(FormField::choice("Indexes", &["copy", "skip"], "copy"));
+    fields.push(FormField::choice("At target", &["merge", "drop"], "merge"));
+    // Dropping needs the target name typed here, as it discards target data.
+    fields.push(FormField::text("Confirm drop", ""));
+
+    state.popup_message_success = None;
+    state.form = Some(FormState::new(
+        format!("Copy {source} to {uri}"),
+        FormPurpose::CopyData { target_uri: uri },
+        fields,
+    ));
+}
+
+fn review_staged_changes(state: &mut AppState) {
+    let Some(changes) = state
+        .staged_changes
+        .as_ref()
+        .filter(|changes| !changes.is_empty())
+    else {
+        state.popup_message = Some("No staged changes to review.".to_string());
+        return;
+    };
+
+    let mut lines = Vec::new();
+    for change in changes {
+        lines.push(PanelLine::new(PanelTone::Header, change.summary()));
+        match change {
+            StagedChange::Insert { document, .. } => {
+                let json =
+                    serde_json::to_string_pretty(document).unwrap_or_else(|_| document.to_string());
+                for line in json.lines() {
+                    lines.push(PanelLine::new(PanelTone::Added, format!("+ {line}")));
+                }
+            }
+            StagedChange::Update {
+                original, edited, ..
+            } => push_diff_lines(&mut lines, original, edited),
+            StagedChange::Delete { .. } => {
+                lines.push(PanelLine::new(PanelTone::Removed, "- whole document"));
+            }
+        }
+        lines.push(PanelLine::new(PanelTone::Normal, ""));
+    }
+
+    state.info_panel = Some(InfoPanel::new(
+        format!(
+            "Staged changes: {} · y: commit · x: discard · Esc: close",
+            changes.len()
+        ),
+        lines,
+    ));
+    state.reviewing_staged = true;
+    state.popup_message_success = None;
+    state.popup_message = state.staged_confirmation_message();
+}
+
+async fn commit_staged(state: &mut AppState) {
+    let Some(client) = state.mongo_client.clone() else {
+        state.popup_message = Some("❌ No active MongoDB connection.".to_string());
+        return;
+    };
+    if let Some(message) = state.staging_connection_mismatch() {
+        state.reviewing_staged = false;
+        state.info_panel = None;
+        state.popup_message = Some(message);
+        return;
+    }
+    let Some(changes) = &state.staged_changes else {
+        return;
+    };
+
+    state.reviewing_staged = false;
+    state.info_panel = None;
+    state.popup_message = None;
+    match commit_staged_changes(&client, changes).await {
+        Ok(count) => {
+            state.staged_changes = Some(Vec::new());
+            state.reload_documents_for_selected_collection().await;
+            state.popup_message_success = Some(format!("✅ Committed {count} staged change(s)"));
+        }
+        Err(error) => {
+            state.popup_message = Some(format!(
+                "❌ Transaction aborted, nothing was written: {error}"
+            ))
+        }
+    }
+}
+
+async fn discard_staged(state: &mut AppState) {
+    let discarded = state
+        .staged_changes
+        .replace(Vec::new())
+        .map(|changes| changes.len())
+        .unwrap_or(0);
+    state.reviewing_staged = false;
+    state.info_panel = None;
+    state.popup_message = None;
+    state.reload_documents_for_selected_collection().await;
+    state.popup_message_success = Some(format!("Discarded {discarded} staged change(s)."));
+}
+
 async fn confirm_bulk_deletion(state: &mut AppState) {
     let Some(deletion) = state.pending_bulk_deletion.take() else {
         return;
@@ -137,42 +853,211 @@ async fn confirm_bulk_deletion(state: &mut AppState) {
 }
 
 pub async fn handle_normal(key: KeyEvent, state: &mut AppState) -> bool {
+    if let Some(job) = &state.copy_job {
+        if key.code == KeyCode::Esc {
+            state.popup_message = Some(format!("Copy cancelled after {} document(s).", job.copied));
+            state.copy_job = None;
+        }
+        return false;
+    }
+
+    if state.dashboard.is_some() {
+        if matches!(
+            key.code,
+            KeyCode::Esc | KeyCode::Char('q') | KeyCode::Char('M')
+        ) {
+            state.dashboard = None;
+        }
+        return false;
+    }
+
+    if state.info_panel.is_some() && handle_panel_key(key, state).await {
+        return false;
+    }
+
     if state.pending_deletion.is_some() {
         match key.code {
-            KeyCode::Char('y') => confirm_deletion(state).await,
+            KeyCode::Char('y') => confirm_deletion(state).await,
+            KeyCode::Char('n') | KeyCode::Esc => {
+                state.pending_deletion = None;
+                state.popup_message = Some("Deletion cancelled.".to_string());
+            }
+            _ => {}
+        }
+        return false;
+    }
+
+    if state.pending_bulk_update.is_some() {
+        match key.code {
+            KeyCode::Char('y') => confirm_bulk_update(state).await,
+            KeyCode::Char('p') => preview_bulk_update(state).await,
+            KeyCode::Char('n') | KeyCode::Esc => {
+                state.pending_bulk_update = None;
+                state.info_panel = None;
+                state.popup_message = Some("Bulk update cancelled.".to_string());
+            }
+            _ => {}
+        }
+        return false;
+    }
+
+    if state.pending_bulk_deletion.is_some() {
+        match key.code {
+            KeyCode::Char('y') => confirm_bulk_deletion(state).await,
+            KeyCode::Char('n') | KeyCode::Esc => {
+                state.pending_bulk_deletion = None;
+                state.popup_message = Some("Bulk deletion cancelled.".to_string());
+            }
+            _ => {}
+        }
+        return false;
+    }
+
+    if let Some(job) = &state.truncate_job {
+        if key.code == KeyCode::Esc {
+            state.popup_message = Some(format!(
+                "Emptying cancelled after {} document(s).",
+                job.deleted
+            ));
+            state.truncate_job = None;
+            state.reload_documents_for_selected_collection().await;
+        }
+        return false;
+    }
+
+    if state.rename_job.is_some() {
+        if key.code == KeyCode::Esc {
+            cancel_database_rename(state).await;
+        }
+        return false;
+    }
+
+    if state.pending_kill_op.is_some() {
+        match key.code {
+            KeyCode::Char('y') => confirm_kill_op(state).await,
+            KeyCode::Char('n') | KeyCode::Esc => {
+                state.pending_kill_op = None;
+                state.popup_message = Some("Kill cancelled.".to_string());
+            }
+            _ => {}
+        }
+        return false;
+    }
+
+    if state.pending_db_rename.is_some() {
+        match key.code {
+            KeyCode::Char('y') => start_database_rename(state).await,
+            KeyCode::Char('n') | KeyCode::Esc => {
+                state.pending_db_rename = None;
+                state.popup_message = Some("Rename cancelled.".to_string());
+            }
+            _ => {}
+        }
+        return false;
+    }
+
+    if state.pending_validation.is_some() {
+        match key.code {
+            KeyCode::Char('y') => confirm_validation(state).await,
+            KeyCode::Char('v') => preview_violators(state).await,
             KeyCode::Char('n') | KeyCode::Esc => {
-                state.pending_deletion = None;
-                state.popup_message = Some("Deletion cancelled.".to_string());
+                state.pending_validation = None;
+                state.info_panel = None;
+                state.popup_message = Some("Validation change cancelled.".to_string());
             }
             _ => {}
         }
         return false;
     }
 
-    if state.pending_bulk_update.is_some() {
+    if state.pending_warned_write.is_some() {
         match key.code {
-            KeyCode::Char('y') => confirm_bulk_update(state).await,
+            KeyCode::Char('y') => confirm_warned_write(state).await,
             KeyCode::Char('n') | KeyCode::Esc => {
-                state.pending_bulk_update = None;
-                state.popup_message = Some("Bulk update cancelled.".to_string());
+                state.pending_warned_write = None;
+                close_info_panel(state);
+                state.popup_message = Some("Write cancelled.".to_string());
             }
             _ => {}
         }
         return false;
     }
 
-    if state.pending_bulk_deletion.is_some() {
+    if state.reviewing_staged {
         match key.code {
-            KeyCode::Char('y') => confirm_bulk_deletion(state).await,
+            KeyCode::Char('y') => commit_staged(state).await,
+            KeyCode::Char('x') => discard_staged(state).await,
             KeyCode::Char('n') | KeyCode::Esc => {
-                state.pending_bulk_deletion = None;
-                state.popup_message = Some("Bulk deletion cancelled.".to_string());
+                state.reviewing_staged = false;
+                state.info_panel = None;
+                state.popup_message = None;
             }
             _ => {}
         }
         return false;
     }
 
+    if state.pending_copy.is_some() {
+        match key.code {
+            KeyCode::Char('p') => {
+                pick_copy_target(state);
+                return false;
+            }
+            KeyCode::Esc => {
+                state.pending_copy = None;
+                state.popup_message_success = None;
+                state.popup_message = Some("Copy cancelled.".to_string());
+                return false;
+            }
+            _ => {}
+        }
+    }
+
+    if let Some(clone) = &mut state.pending_clone {
+        if !clone.picking_target {
+            match key.code {
+                KeyCode::Char('y') => {
+                    let (db, collection) = (clone.db.clone(), clone.collection.clone());
+                    insert_clone(state, &db, &collection).await;
+                }
+                KeyCode::Char('t') => {
+                    clone.picking_target = true;
+                    state.focus = FocusArea::Connections;
+                    state.popup_message = None;
+                    state.popup_message_success = Some(
+                        "Select a target collection or database and press p (Esc cancels)"
+                            .to_string(),
+                    );
+                }
+                KeyCode::Char('n') | KeyCode::Esc => {
+                    state.pending_clone = None;
+                    state.popup_message = Some("Clone cancelled.".to_string());
+                }
+                _ => {}
+            }
+            return false;
+        }
+
+        match key.code {
+            KeyCode::Char('p') => {
+                pick_clone_target(state).await;
+                return false;
+            }
+            KeyCode::Esc => {
+                state.pending_clone = None;
+                state.popup_message_success = None;
+                state.popup_message = Some("Clone cancelled.".to_string());
+                return false;
+            }
+            // Only tree navigation stays available while picking the target.
+            KeyCode::Char('j' | 'k' | 'n' | 'N' | '/')
+            | KeyCode::Up
+            | KeyCode::Down
+            | KeyCode::Enter => {}
+            _ => return false,
+        }
+    }
+
     match key.code {
         KeyCode::Char('q') => return true,
 
@@ -329,6 +1214,19 @@ pub async fn handle_normal(key: KeyEvent, state: &mut AppState) -> bool {
                             }
                         }
                     }
+                    FocusArea::Documents if state.staged_changes.is_some() => {
+                        if let (Some(doc), Some((_, db, collection))) = (
+                            state.current_documents.get(state.selected_doc_index),
+                            &state.selected_collection,
+                        ) {
+                            let change = StagedChange::Delete {
+                                db: db.clone(),
+                                collection: collection.clone(),
+                                document: doc.clone(),
+                            };
+                            state.stage(change);
+                        }
+                    }
                     FocusArea::Documents => {
                         if let Some(doc) = state.current_documents.get(state.selected_doc_index) {
                             if let Some(id) = doc.get_object_id("_id").ok() {
@@ -378,6 +1276,23 @@ pub async fn handle_normal(key: KeyEvent, state: &mut AppState) -> bool {
 
                 if let Some((field, id)) = maybe_id_and_field {
                     if let Some((_, db, collection)) = &state.selected_collection {
+                        if let Some(original) = state
+                            .current_documents
+                            .get(state.selected_doc_index)
+                            .filter(|_| state.staged_changes.is_some())
+                        {
+                            let mut edited = original.clone();
+                            edited.remove(&field);
+                            let change = StagedChange::Update {
+                                db: db.clone(),
+                                collection: collection.clone(),
+                                original: original.clone(),
+                                edited,
+                            };
+                            state.stage(change);
+                            return false;
+                        }
+
                         state.pending_deletion = Some(PendingDeletion::Field {
                             db: db.clone(),
                             collection: collection.clone(),
@@ -448,6 +1363,14 @@ pub async fn handle_normal(key: KeyEvent, state: &mut AppState) -> bool {
                 }
             }
             _ => {
+                // Renaming a database runs through the active client.
+                if matches!(
+                    state.tree_items.get(state.selected_index),
+                    Some(SelectableItem::Database { .. })
+                ) && refuse_other_connection(state)
+                {
+                    return false;
+                }
                 if let Some(item) = state.tree_items.get(state.selected_index) {
                     match item {
                         SelectableItem::Uri { uri, .. } => {
@@ -460,6 +1383,12 @@ pub async fn handle_normal(key: KeyEvent, state: &mut AppState) -> bool {
                                 state.cursor_position = state.input_text.chars().count();
                             }
                         }
+                        SelectableItem::Database { name, .. } => {
+                            state.mode = AppMode::Insert;
+                            state.input_context = InputContext::DatabaseName;
+                            state.input_text = name.clone();
+                            state.cursor_position = state.input_text.chars().count();
+                        }
                         SelectableItem::Collection { uri, db, name } => {
                             state.mode = AppMode::Insert;
                             state.input_context = InputContext::CollectionName;
@@ -468,12 +1397,558 @@ pub async fn handle_normal(key: KeyEvent, state: &mut AppState) -> bool {
                                 Some((uri.clone(), db.clone(), name.clone()));
                             state.cursor_position = state.input_text.chars().count();
                         }
-                        _ => {}
                     }
                 }
             }
         },
 
+        KeyCode::Char('c') if state.focus == FocusArea::Documents => {
+            let Some((_, db, collection)) = &state.selected_collection else {
+                state.popup_message = Some("❌ No collection selected.".to_string());
+                return false;
+            };
+            let Some(doc) = state.current_documents.get(state.selected_doc_index) else {
+                return false;
+            };
+            let Some((field, value)) = doc.iter().nth(state.selected_field_index) else {
+                return false;
+            };
+            if field == "_id" {
+                state.popup_message = Some("❌ Cannot edit _id field.".to_string());
+                return false;
+            }
+            let Some(value_type) = ValueType::of(value) else {
+                state.popup_message =
+                    Some("❗ Use e to edit documents, arrays and other types.".to_string());
+                return false;
+            };
+            let Some(id) = doc.get("_id") else {
+                state.popup_message = Some("❌ Document has no _id field.".to_string());
+                return false;
+            };
+
+            state.form = Some(FormState::new(
+                format!("Edit field '{field}'"),
+                FormPurpose::EditField {
+                    db: db.clone(),
+                    collection: collection.clone(),
+                    id: id.clone(),
+                    path: field.to_string(),
+                },
+                vec![
+                    FormField::text("Value", editable_text(value)),
+                    FormField::choice(
+                        "Type",
+                        &ValueType::ALL.map(ValueType::as_str),
+                        value_type.as_str(),
+                    ),
+                ],
+            ));
+        }
+
+        KeyCode::Char('C') if state.focus == FocusArea::Documents => {
+            let Some((_, db, collection)) = state.selected_collection.clone() else {
+                state.popup_message = Some("❌ No collection selected.".to_string());
+                return false;
+            };
+            match open_clone_editor(state) {
+                Ok(Some(document)) => {
+                    let clone = PendingClone {
+                        document,
+                        db,
+                        collection,
+                        picking_target: false,
+                        target_db: None,
+                    };
+                    state.popup_message_success = None;
+                    state.popup_message = Some(clone.confirmation_message());
+                    state.pending_clone = Some(clone);
+                }
+                Ok(None) => {}
+                Err(error) => state.popup_message = Some(format!("❌ {error}")),
+            }
+        }
+
+        KeyCode::Char('c') if state.focus == FocusArea::Connections => {
+            if refuse_other_connection(state) {
+                return false;
+            }
+            let (Some(source), Some(uri)) =
+                (state.mongo_client.clone(), state.connected_uri.clone())
+            else {
+                state.popup_message = Some("❌ No active MongoDB connection.".to_string());
+                return false;
+            };
+            let (db, collection) = match state.tree_items.get(state.selected_index) {
+                Some(SelectableItem::Collection { db, name, .. }) => {
+                    (db.clone(), Some(name.clone()))
+                }
+                Some(SelectableItem::Database { name, .. }) => (name.clone(), None),
+                _ => {
+                    state.popup_message = Some("❗ Select a collection or database.".to_string());
+                    return false;
+                }
+            };
+            let filter = match &state.selected_collection {
+                Some((_, loaded_db, loaded))
+                    if collection.as_ref() == Some(loaded) && *loaded_db == db =>
+                {
+                    state.filter_text.clone()
+                }
+                _ => "{}".to_string(),
+            };
+
+            state.pending_copy = Some(PendingCopy {
+                source,
+                uri,
+                db,
+                collection,
+                filter,
+            });
+            state.popup_message = None;
+            state.popup_message_success = Some(
+                "Select a target database or collection (Enter connects to another connection) and press p (Esc cancels)"
+                    .to_string(),
+            );
+        }
+
+        KeyCode::Char('a') if state.focus == FocusArea::Connections => {
+            if refuse_other_connection(state) {
+                return false;
+            }
+            let (uri, db) = match state.tree_items.get(state.selected_index) {
+                Some(SelectableItem::Uri {
+                    uri,
+                    connected: true,
+                    ..
+                }) => (uri.clone(), None),
+                Some(SelectableItem::Database { uri, name }) => (uri.clone(), Some(name.clone())),
+                _ => {
+                    state.popup_message = Some(
+                        "❗ Select a connected connection or a database to create in.".to_string(),
+                    );
+                    return false;
+                }
+            };
+
+            let mut fields = Vec::new();
+            if db.is_none() {
+                fields.push(FormField::text("Database", ""));
+            }
+            fields.extend([
+                FormField::text("Collection", ""),
+                FormField::choice(
+                    "Kind",
+                    &["standard", "capped", "timeseries", "clustered"],
+                    "standard",
+                ),
+                FormField::text("Capped size", ""),
+                FormField::text("Capped max", ""),
+                FormField::text("Time field", ""),
+                FormField::text("Meta field", ""),
+                FormField::choice("Granularity", &["seconds", "minutes", "hours"], "seconds"),
+                FormField::text("Collation", ""),
+                FormField::text("Validator", ""),
+            ]);
+            let title = match &db {
+                Some(db) => format!("New collection in {db}"),
+                None => "New database".to_string(),
+            };
+            state.form = Some(FormState::new(
+                title,
+                FormPurpose::CreateCollection { uri, db },
+                fields,
+            ));
+        }
+
+        KeyCode::Char('K') => {
+            if refuse_other_connection(state) {
+                return false;
+            }
+            let target = match state.focus {
+                FocusArea::Connections => match state.tree_items.get(state.selected_index) {
+                    Some(SelectableItem::Collection { db, name, .. }) => {
+                        Some((db.clone(), name.clone()))
+                    }
+                    _ => None,
+                },
+                FocusArea::Documents => state
+                    .selected_collection
+                    .as_ref()
+                    .map(|(_, db, name)| (db.clone(), name.clone())),
+            };
+            match target {
+                Some((db, collection)) => open_index_panel(state, &db, &collection).await,
+                None => state.popup_message = Some("❗ Select a collection first.".to_string()),
+            }
+        }
+
+        KeyCode::Char('R') if state.focus == FocusArea::Connections => {
+            if refuse_other_connection(state) {
+                return false;
+            }
+            let target = match state.tree_items.get(state.selected_index) {
+                Some(SelectableItem::Collection { db, name, .. }) => {
+                    Some((db.clone(), Some(name.clone())))
+                }
+                Some(SelectableItem::Database { name, .. }) => Some((name.clone(), None)),
+                _ => None,
+            };
+            let Some((db, collection)) = target else {
+                state.popup_message = Some("❗ Select a database or collection.".to_string());
+                return false;
+            };
+            let Some(client) = state.mongo_client.clone() else {
+                state.popup_message = Some("❌ No active MongoDB connection.".to_string());
+                return false;
+            };
+
+            let collections = match collection {
+                Some(collection) => vec![collection],
+                None => match crate::db::client::list_collections(&client, &db).await {
+                    Ok(collections) => collections
+                        .into_iter()
+                        .filter(|name| !name.starts_with("system."))
+                        .collect(),
+                    Err(error) => {
+                        state.popup_message =
+                            Some(format!("❌ Could not list collections: {error}"));
+                        return false;
+                    }
+                },
+            };
+            open_index_report(state, &db, collections).await;
+        }
+
+        KeyCode::Char('S') => {
+            if refuse_other_connection(state) {
+                return false;
+            }
+            let target = match state.focus {
+                FocusArea::Connections => match state.tree_items.get(state.selected_index) {
+                    Some(SelectableItem::Collection { db, name, .. }) => {
+                        Some((db.clone(), Some(name.clone())))
+                    }
+                    Some(SelectableItem::Database { name, .. }) => Some((name.clone(), None)),
+                    _ => None,
+                },
+                FocusArea::Documents => state
+                    .selected_collection
+                    .as_ref()
+                    .map(|(_, db, name)| (db.clone(), Some(name.clone()))),
+            };
+            match target {
+                Some((db, collection)) => open_stats_panel(state, &db, collection.as_deref()).await,
+                None => {
+                    state.popup_message = Some("❗ Select a database or collection.".to_string())
+                }
+            }
+        }
+
+        KeyCode::Char('v') if state.focus == FocusArea::Connections => {
+            let Some(SelectableItem::Collection { uri, db, name }) =
+                state.tree_items.get(state.selected_index)
+            else {
+                state.popup_message = Some("❗ Select a view.".to_string());
+                return false;
+            };
+            let Some(view) = state.views.get(&(uri.clone(), db.clone(), name.clone())) else {
+                state.popup_message = Some(format!("❗ {db}.{name} is not a view."));
+                return false;
+            };
+
+            let mut lines = vec![
+                PanelLine::new(PanelTone::Header, "viewOn"),
+                PanelLine::new(PanelTone::Normal, format!("  {db}.{}", view.view_on)),
+                PanelLine::new(PanelTone::Normal, ""),
+                PanelLine::new(PanelTone::Header, "pipeline"),
+            ];
+            let pipeline = serde_json::to_string_pretty(&view.pipeline)
+                .unwrap_or_else(|error| format!("⚠️ Could not render pipeline: {error}"));
+            lines.extend(
+                pipeline
+                    .lines()
+                    .map(|line| PanelLine::new(PanelTone::Normal, format!("  {line}"))),
+            );
+            let title = format!("View: {db}.{name} · Esc: close");
+            state.popup_message = None;
+            state.info_panel = Some(InfoPanel::new(title, lines));
+        }
+
+        KeyCode::Char('E') if state.focus == FocusArea::Documents => {
+            if refuse_other_connection(state) {
+                return false;
+            }
+            let Some((uri, db, collection)) = state.selected_collection.clone() else {
+                state.popup_message = Some("❌ No collection selected.".to_string());
+                return false;
+            };
+            open_export_form(state, &uri, &db, &collection);
+        }
+
+        KeyCode::Char('W') if state.focus == FocusArea::Documents => {
+            if refuse_other_connection(state) {
+                return false;
+            }
+            let Some((uri, db, collection)) = &state.selected_collection else {
+                state.popup_message = Some("❌ No collection selected.".to_string());
+                return false;
+            };
+            state.form = Some(FormState::new(
+                format!("New view on {db}.{collection}"),
+                FormPurpose::CreateView {
+                    uri: uri.clone(),
+                    db: db.clone(),
+                    view_on: collection.clone(),
+                },
+                vec![
+                    FormField::text("Name", ""),
+                    FormField::choice("Source", &["filter", "pipeline"], "filter"),
+                    FormField::text("Filter", state.filter_text.clone()),
+                    FormField::text("Sort", state.sort_text.clone()),
+                    FormField::text("Projection", "{}"),
+                    FormField::text("Pipeline", "[]"),
+                ],
+            ));
+        }
+
+        KeyCode::Char('P') => {
+            if refuse_other_connection(state) {
+                return false;
+            }
+            let db = match state.focus {
+                FocusArea::Connections => match state.tree_items.get(state.selected_index) {
+                    Some(SelectableItem::Database { name, .. }) => Some(name.clone()),
+                    Some(SelectableItem::Collection { db, .. }) => Some(db.clone()),
+                    _ => None,
+                },
+                FocusArea::Documents => state
+                    .selected_collection
+                    .as_ref()
+                    .map(|(_, db, _)| db.clone()),
+            };
+            match db {
+                Some(db) => open_profiler_panel(state, &db).await,
+                None => state.popup_message = Some("❗ Select a database.".to_string()),
+            }
+        }
+
+        KeyCode::Char('H') => {
+            if refuse_other_connection(state) {
+                return false;
+            }
+            open_replset_panel(state).await
+        }
+
+        KeyCode::Char('M') => {
+            if refuse_other_connection(state) {
+                return false;
+            }
+            if state.mongo_client.is_none() {
+                state.popup_message = Some("❌ No active MongoDB connection.".to_string());
+                return false;
+            }
+            state.dashboard = Some(Default::default());
+        }
+
+        KeyCode::Char('O') => {
+            if refuse_other_connection(state) {
+                return false;
+            }
+            open_ops_panel(state, Default::default(), false).await
+        }
+
+        KeyCode::Char('T') => {
+            if refuse_other_connection(state) {
+                return false;
+            }
+            let target = match state.focus {
+                FocusArea::Connections => match state.tree_items.get(state.selected_index) {
+                    Some(SelectableItem::Collection { db, name, .. }) => {
+                        Some((db.clone(), name.clone()))
+                    }
+                    _ => None,
+                },
+                FocusArea::Documents => state
+                    .selected_collection
+                    .as_ref()
+                    .map(|(_, db, name)| (db.clone(), name.clone())),
+            };
+            let Some((db, collection)) = target else {
+                state.popup_message = Some("❗ Select a collection.".to_string());
+                return false;
+            };
+            let deletion = PendingDeletion::AllDocuments { db, collection };
+            state.popup_message_success = None;
+            state.popup_message = Some(deletion.confirmation_message());
+            state.pending_deletion = Some(deletion);
+        }
+
+        KeyCode::Char('V') => {
+            if refuse_other_connection(state) {
+                return false;
+            }
+            let target = match state.focus {
+                FocusArea::Connections => match state.tree_items.get(state.selected_index) {
+                    Some(SelectableItem::Collection { db, name, .. }) => {
+                        Some((db.clone(), name.clone()))
+                    }
+                    _ => None,
+                },
+                FocusArea::Documents => state
+                    .selected_collection
+                    .as_ref()
+                    .map(|(_, db, name)| (db.clone(), name.clone())),
+            };
+            let Some((db, collection)) = target else {
+                state.popup_message = Some("❗ Select a collection.".to_string());
+                return false;
+            };
+            match open_validation_editor(state, &db, &collection).await {
+                Ok(Some(validation)) => {
+                    state.popup_message_success = None;
+                    state.popup_message = Some(validation.confirmation_message());
+                    state.pending_validation = Some(validation);
+                }
+                Ok(None) => {
+                    state.popup_message_success = Some("Validation rules unchanged.".to_string())
+                }
+                Err(error) => state.popup_message = Some(format!("❌ {error}")),
+            }
+        }
+
+        KeyCode::Char('u')
+            if state.focus == FocusArea::Connections
+                && !key.modifiers.contains(KeyModifiers::CONTROL) =>
+        {
+            if refuse_other_connection(state) {
+                return false;
+            }
+            let db = match state.tree_items.get(state.selected_index) {
+                Some(SelectableItem::Database { name, .. }) => name.clone(),
+                Some(SelectableItem::Collection { db, .. }) => db.clone(),
+                Some(SelectableItem::Uri {
+                    connected: true, ..
+                }) => "admin".to_string(),
+                _ => {
+                    state.popup_message = Some("❗ Select a database.".to_string());
+                    return false;
+                }
+            };
+            open_users_panel(state, &db).await;
+        }
+
+        KeyCode::Char('z') if state.focus == FocusArea::Connections => {
+            state.show_tree_hints = !state.show_tree_hints;
+            if state.show_tree_hints {
+                let expanded: Vec<(String, String)> = state
+                    .expanded_dbs
+                    .iter()
+                    .filter(|(uri, _)| state.connected_uri.as_ref() == Some(uri))
+                    .cloned()
+                    .collect();
+                for (uri, db) in expanded {
+                    crate::db::handler::load_collection_hints(state, &uri, &db).await;
+                }
+            }
+        }
+
+        KeyCode::Char('b') => match &state.staged_changes {
+            None => {
+                let Some(uri) = state.connected_uri.clone() else {
+                    state.popup_message = Some("❌ No active MongoDB connection.".to_string());
+                    return false;
+                };
+                state.staged_changes = Some(Vec::new());
+                state.staging_uri = Some(uri);
+                state.popup_message_success = Some(
+                    "📝 Staging on: edits, deletes and clones are buffered (B: review and commit)"
+                        .to_string(),
+                );
+            }
+            Some(changes) if changes.is_empty() => {
+                state.staged_changes = None;
+                state.staging_uri = None;
+                state.popup_message_success =
+                    Some("Staging off: changes apply directly.".to_string());
+            }
+            Some(changes) => {
+                state.popup_message = Some(format!(
+                    "❗ Commit or discard {} staged change(s) first (B).",
+                    changes.len()
+                ));
+            }
+        },
+
+        KeyCode::Char('B') => review_staged_changes(state),
+
+        KeyCode::Char('A') if state.focus == FocusArea::Documents => {
+            if refuse_other_connection(state) {
+                return false;
+            }
+            let Some((_, db, collection)) = &state.selected_collection else {
+                state.popup_message = Some("❌ No collection selected.".to_string());
+                return false;
+            };
+            state.form = Some(FormState::new(
+                format!("Analyze schema of {db}.{collection}"),
+                FormPurpose::AnalyzeSchema {
+                    db: db.clone(),
+                    collection: collection.clone(),
+                },
+                vec![
+                    FormField::choice("Source", &["sample", "current filter"], "sample"),
+                    FormField::text("Documents", "1000"),
+                ],
+            ));
+        }
+
+        KeyCode::Char('R') | KeyCode::Char('U') | KeyCode::Char('X')
+            if state.focus == FocusArea::Documents && state.staged_changes.is_some() =>
+        {
+            state.popup_message = Some(
+                "❗ Bulk changes write directly; commit and turn off staging (b) first."
+                    .to_string(),
+            );
+        }
+
+        KeyCode::Char('R') if state.focus == FocusArea::Documents => {
+            let Some((_, db, collection)) = &state.selected_collection else {
+                state.popup_message = Some("❌ No collection selected.".to_string());
+                return false;
+            };
+            let Some((field, _)) = state
+                .current_documents
+                .get(state.selected_doc_index)
+                .and_then(|doc| doc.iter().nth(state.selected_field_index))
+            else {
+                return false;
+            };
+            if field == "_id" {
+                state.popup_message = Some("❌ Cannot refactor the _id field.".to_string());
+                return false;
+            }
+
+            let convertible = ValueType::ALL
+                .into_iter()
+                .filter(|value_type| *value_type != ValueType::Null)
+                .map(ValueType::as_str)
+                .collect::<Vec<_>>();
+            state.form = Some(FormState::new(
+                format!("Refactor field '{field}' across the collection"),
+                FormPurpose::RefactorField {
+                    db: db.clone(),
+                    collection: collection.clone(),
+                    path: field.to_string(),
+                },
+                vec![
+                    FormField::choice("Operation", &["rename", "convert", "remove"], "rename"),
+                    FormField::text("New name", field),
+                    FormField::choice("Convert to", &convertible, "string"),
+                ],
+            ));
+        }
+
         KeyCode::Char('U') if state.focus == FocusArea::Documents => {
             match open_bulk_update_editor(state).await {
                 Ok(Some(update)) => {
@@ -549,28 +2024,15 @@ pub async fn handle_normal(key: KeyEvent, state: &mut AppState) -> bool {
             state.collection_search_idx = 0;
         }
         KeyCode::Char('x') => {
+            if refuse_other_connection(state) {
+                return false;
+            }
             if let Some(item) = state.tree_items.get(state.selected_index) {
                 if let Some(client) = &state.mongo_client {
                     match item {
-                        SelectableItem::Collection { uri: _, db, name } => {
-                            let path =
-                                crate::utils::get_data_dir().join(format!("{}_{}.json", db, name));
-                            match crate::db::import_export::export_collection(
-                                client,
-                                db,
-                                name,
-                                path.to_str().unwrap(),
-                            )
-                            .await
-                            {
-                                Ok(_) => {
-                                    state.popup_message_success =
-                                        Some(format!("✅ Exported collection: {}", path.display()))
-                                }
-                                Err(e) => {
-                                    state.popup_message = Some(format!("❌ Export failed: {}", e))
-                                }
-                            }
+                        SelectableItem::Collection { uri, db, name } => {
+                            let (uri, db, name) = (uri.clone(), db.clone(), name.clone());
+                            open_export_form(state, &uri, &db, &name);
                         }
 
                         SelectableItem::Database {
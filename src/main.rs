@@ -9,7 +9,10 @@ use crossterm::{
     execute,
     terminal::{EnterAlternateScreen, LeaveAlternateScreen, disable_raw_mode, enable_raw_mode},
 };
-use db::handler::{fetch_and_update_documents, handle_collection_listing, handle_connection};
+use db::handler::{
+    advance_copy_job, advance_rename_job, advance_truncate_job, fetch_and_update_documents,
+    handle_collection_listing, handle_connection,
+};
 use ratatui::{
     Terminal,
     backend::CrosstermBackend,
@@ -18,14 +21,20 @@ use ratatui::{
     widgets::Block,
 };
 use std::io;
+use tui::dashboard::poll_dashboard_if_due;
 use tui::events::{handle_key_event, handle_paste_event};
 use tui::input::render_input;
+use tui::ops_panel::refresh_ops_panel_if_due;
 use widgets::{
     connection_panel::render_connections,
+    dashboard::render_dashboard,
     documents::render_documents,
+    form::render_form,
     help_popup::draw_help_popup,
     import::{centered_rect, render_file_picker},
+    info_panel::render_info_panel,
     popup::{render_popup, render_popup_success},
+    progress::render_progress,
     toolbar::render_status_bar,
 };
 
@@ -35,11 +44,13 @@ mod keybindings;
 mod theme;
 mod tui;
 mod utils;
+mod value_type;
 mod widgets;
 
 fn apply_cursor_style(state: &AppState) {
     let style = match state.mode {
         AppMode::Insert => SetCursorStyle::SteadyBar,
+        AppMode::Normal if state.form.is_some() => SetCursorStyle::SteadyBar,
         AppMode::Normal => SetCursorStyle::SteadyBlock,
     };
     let _ = execute!(io::stdout(), style);
@@ -91,6 +102,9 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
             render_connections(f, content_chunks[0], &state);
             render_documents(f, content_chunks[1], &state);
             render_status_bar(f, chunks[1], &state);
+            if let Some(panel) = &mut state.info_panel {
+                render_info_panel(f, f.area(), panel, &theme);
+            }
             render_popup(f, f.area(), &state);
             render_popup_success(f, f.area(), &state);
 
@@ -104,6 +118,36 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
                 render_file_picker(f, popup_area, picker, &theme);
             }
 
+            if let Some(dashboard) = &state.dashboard {
+                render_dashboard(f, f.area(), dashboard, &theme);
+            }
+
+            if let Some(job) = &state.copy_job {
+                let title = format!(
+                    "Copying {} → {} · collection {}/{}",
+                    job.source_db,
+                    job.target_db,
+                    (job.collections_done() + 1).min(job.collections),
+                    job.collections
+                );
+                render_progress(f, f.area(), &title, job.copied, job.total, &theme);
+            }
+
+            if let Some(job) = &state.truncate_job {
+                let title = format!("Emptying {}.{}", job.db, job.name);
+                render_progress(f, f.area(), &title, job.deleted, job.total, &theme);
+            }
+
+            if let Some(job) = &state.rename_job {
+                let title = format!("Renaming {} → {}", job.source_db, job.target_db);
+                let (done, steps) = (job.done as u64, job.steps as u64);
+                render_progress(f, f.area(), &title, done, steps, &theme);
+            }
+
+            if let Some(form) = &state.form {
+                render_form(f, f.area(), form, &theme);
+            }
+
             if state.show_help {
                 let area = centered_rect(70, 70, f.area());
                 draw_help_popup(f, area, state.help_scroll, &theme);
@@ -124,7 +168,26 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
             fetch_and_update_documents(&mut state, &uri, &db, &name).await;
         }
 
-        if event::poll(std::time::Duration::from_millis(100))? {
+        if state.copy_job.is_some() {
+            advance_copy_job(&mut state).await;
+        }
+
+        refresh_ops_panel_if_due(&mut state).await;
+        poll_dashboard_if_due(&mut state).await;
+
+        if state.rename_job.is_some() {
+            advance_rename_job(&mut state).await;
+        }
+
+        if state.truncate_job.is_some() {
+            advance_truncate_job(&mut state).await;
+        }
+
+        // Keep batches flowing while a job runs, but still pick up Esc.
+        let busy =
+            state.copy_job.is_some() || state.rename_job.is_some() || state.truncate_job.is_some();
+        let timeout = if busy { 0 } else { 100 };
+        if event::poll(std::time::Duration::from_millis(timeout))? {
             match event::read()? {
                 Event::Key(key_event) => {
                     let should_exit = handle_key_event(key_event, &mut state).await;
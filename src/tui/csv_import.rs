@@ -0,0 +1,81 @@
+use crate::app::AppState;
+use crate::db::csv::{COLUMN_TYPES, INFERENCE_SAMPLE, infer_type};
+use crate::db::import_export::{DelimitedFile, read_delimited};
+use crate::tui::form::{FormField, FormPurpose, FormState};
+use std::path::{Path, PathBuf};
+
+/// Data rows shown under the column mapping.
+const PREVIEW_ROWS: usize = 5;
+
+/// CSV and TSV files go through the column mapping instead of the JSON import.
+pub fn delimiter_for(path: &Path) -> Option<char> {
+    match path.extension().and_then(|ext| ext.to_str()) {
+        Some("csv") => Some(','),
+        Some("tsv") => Some('\t'),
+        _ => None,
+    }
+}
+
+/// Opens the column mapping for the first readable file in `files`. Each
+/// column gets a type guessed from the first rows; the collection defaults
+/// to the file name.
+pub fn open_csv_import(state: &mut AppState, uri: &str, db: &str, mut files: Vec<PathBuf>) {
+    while !files.is_empty() {
+        let path = files.remove(0);
+        let Some(delimiter) = delimiter_for(&path) else {
+            continue;
+        };
+        let DelimitedFile { headers, rows } =
+            match read_delimited(&path.to_string_lossy(), delimiter) {
+                Ok(contents) => contents,
+                Err(error) => {
+                    state.popup_message =
+                        Some(format!("❌ Could not read {}: {error}", path.display()));
+                    continue;
+                }
+            };
+
+        let collection = path
+            .file_stem()
+            .and_then(|stem| stem.to_str())
+            .unwrap_or("unnamed");
+        let mut fields = vec![FormField::text("Collection", collection)];
+        for (column, header) in headers.iter().enumerate() {
+            let samples = rows
+                .iter()
+                .take(INFERENCE_SAMPLE)
+                .map(|row| row.get(column).map(String::as_str).unwrap_or(""));
+            let guess = infer_type(samples);
+            fields.push(FormField::choice(header, &COLUMN_TYPES, guess.label()));
+        }
+
+        let mut preview = vec![format!(
+            "Preview · {} row(s) · dotted headers become nested fields",
+            rows.len()
+        )];
+        preview.push(headers.join(" │ "));
+        preview.extend(rows.iter().take(PREVIEW_ROWS).map(|row| row.join(" │ ")));
+
+        let title = format!(
+            "Import {} into {db}",
+            path.file_name()
+                .map(|name| name.to_string_lossy())
+                .unwrap_or_default()
+        );
+        state.form = Some(
+            FormState::new(
+                title,
+                FormPurpose::ImportDelimited {
+                    uri: uri.to_string(),
+                    db: db.to_string(),
+                    path,
+                    delimiter,
+                    remaining: files,
+                },
+                fields,
+            )
+            .with_preview(preview),
+        );
+        return;
+    }
+}
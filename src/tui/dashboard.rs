@@ -0,0 +1,71 @@
+use crate::app::AppState;
+use crate::db::server_status::{ServerSample, server_status};
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+pub const DASHBOARD_INTERVAL: Duration = Duration::from_secs(2);
+/// Samples kept for the sparklines; rates need one more than they plot.
+const DASHBOARD_SAMPLES: usize = 121;
+
+#[derive(Default)]
+pub struct Dashboard {
+    pub samples: VecDeque<ServerSample>,
+    pub polled_at: Option<Instant>,
+    pub error: Option<String>,
+}
+
+impl Dashboard {
+    pub fn latest(&self) -> Option<&ServerSample> {
+        self.samples.back()
+    }
+
+    /// Values of a gauge such as connections, oldest first.
+    pub fn series(&self, value: impl Fn(&ServerSample) -> i64) -> Vec<u64> {
+        self.samples
+            .iter()
+            .map(|sample| value(sample).max(0) as u64)
+            .collect()
+    }
+
+    /// Per-second rates of a counter between consecutive samples.
+    pub fn rates(&self, value: impl Fn(&ServerSample) -> i64) -> Vec<u64> {
+        self.samples
+            .iter()
+            .zip(self.samples.iter().skip(1))
+            .map(|(previous, next)| next.rate(previous, &value))
+            .collect()
+    }
+}
+
+/// Called from the main loop; polls `serverStatus` while the dashboard is
+/// open.
+pub async fn poll_dashboard_if_due(state: &mut AppState) {
+    let Some(dashboard) = &state.dashboard else {
+        return;
+    };
+    if dashboard
+        .polled_at
+        .is_some_and(|polled_at| polled_at.elapsed() < DASHBOARD_INTERVAL)
+    {
+        return;
+    }
+    let Some(client) = state.mongo_client.clone() else {
+        return;
+    };
+
+    let result = server_status(&client).await;
+    let Some(dashboard) = &mut state.dashboard else {
+        return;
+    };
+    dashboard.polled_at = Some(Instant::now());
+    match result {
+        Ok(sample) => {
+            dashboard.samples.push_back(sample);
+            if dashboard.samples.len() > DASHBOARD_SAMPLES {
+                dashboard.samples.pop_front();
+            }
+            dashboard.error = None;
+        }
+        Err(error) => dashboard.error = Some(error.to_string()),
+    }
+}
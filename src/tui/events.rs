@@ -1,7 +1,7 @@
 use crate::app::{AppMode, AppState};
 use crate::app::{FocusArea, SelectableItem};
 use crate::keybindings::{handle_by_mode, insert};
-use crate::tui::fpicker_events;
+use crate::tui::{form_events, fpicker_events};
 use crossterm::event::KeyEvent;
 
 pub fn is_braced_object(s: &str) -> bool {
@@ -29,10 +29,23 @@ pub async fn handle_key_event(key: KeyEvent, state: &mut AppState) -> bool {
         return false;
     }
 
+    if state.form.is_some() {
+        let should_close = form_events::handle_form_key(key, state).await;
+        if should_close {
+            state.form = None;
+        }
+        return false;
+    }
+
     handle_by_mode(key, state).await
 }
 
 pub fn handle_paste_event(text: String, state: &mut AppState) {
+    if let Some(form) = &mut state.form {
+        form.insert_text(text.trim_end_matches('\n'));
+        return;
+    }
+
     if state.file_picker.is_some() || state.mode != AppMode::Insert {
         return;
     }
@@ -1,5 +1,5 @@
-use crate::db::import_export::import_collection;
-use mongodb::Client;
+use crate::db::import_export::{PreflightError, import_collection};
+use mongodb::{Client, bson::Document};
 use std::collections::HashSet;
 use std::fs::DirEntry;
 use std::path::{Path, PathBuf};
@@ -15,6 +15,16 @@ pub enum FilePickerMode {
     RunScript,
 }
 
+/// What a JSON collection import did, file by file.
+pub struct ImportSummary {
+    pub success: usize,
+    pub failed: usize,
+    /// Pre-flight violations, prefixed with their file.
+    pub report: Vec<String>,
+    /// Documents per collection whose violations the validator only warns about.
+    pub warned: Vec<(String, Vec<Document>)>,
+}
+
 pub struct FilePickerState {
     pub mode: FilePickerMode,
     pub current_path: PathBuf,
@@ -39,9 +49,11 @@ impl FilePickerState {
         client: &Client,
         db_name: &str,
         _uri: &str,
-    ) -> (usize, usize) {
+    ) -> ImportSummary {
         let mut success = 0;
         let mut failed = 0;
+        let mut report = Vec::new();
+        let mut warned = Vec::new();
 
         for path in &self.selected_files {
             if path.extension().and_then(|e| e.to_str()) == Some("json") {
@@ -54,13 +66,30 @@ impl FilePickerState {
                     Ok(_) => success += 1,
                     Err(e) => {
                         failed += 1;
-                        eprintln!("❌ Failed to import {}: {}", path.display(), e);
+                        match e.downcast_ref::<PreflightError>() {
+                            Some(preflight) => {
+                                report.extend(
+                                    preflight.violations.iter().map(|violation| {
+                                        format!("{}: {violation}", path.display())
+                                    }),
+                                );
+                                if let Some(documents) = &preflight.documents {
+                                    warned.push((file_stem.to_string(), documents.clone()));
+                                }
+                            }
+                            None => eprintln!("❌ Failed to import {}: {}", path.display(), e),
+                        }
                     }
                 }
             }
         }
 
-        (success, failed)
+        ImportSummary {
+            success,
+            failed,
+            report,
+            warned,
+        }
     }
 
     fn read_entries_with_parent(path: &Path) -> std::io::Result<Vec<FileEntry>> {
@@ -0,0 +1,238 @@
+use mongodb::bson::Bson;
+use std::path::PathBuf;
+
+pub enum FormFieldKind {
+    Text,
+    /// Text that is masked on screen, for passwords.
+    Secret,
+    Choice(Vec<String>),
+}
+
+pub struct FormField {
+    pub label: String,
+    pub value: String,
+    pub kind: FormFieldKind,
+}
+
+impl FormField {
+    pub fn text(label: &str, value: impl Into<String>) -> Self {
+        Self {
+            label: label.to_string(),
+            value: value.into(),
+            kind: FormFieldKind::Text,
+        }
+    }
+
+    pub fn secret(label: &str) -> Self {
+        Self {
+            label: label.to_string(),
+            value: String::new(),
+            kind: FormFieldKind::Secret,
+        }
+    }
+
+    pub fn choice(label: &str, options: &[&str], selected: &str) -> Self {
+        let options: Vec<String> = options.iter().map(|option| option.to_string()).collect();
+        let value = if options.iter().any(|option| option == selected) {
+            selected.to_string()
+        } else {
+            options.first().cloned().unwrap_or_default()
+        };
+        Self {
+            label: label.to_string(),
+            value,
+            kind: FormFieldKind::Choice(options),
+        }
+    }
+}
+
+pub enum FormPurpose {
+    EditField {
+        db: String,
+        collection: String,
+        id: Bson,
+        path: String,
+    },
+    RefactorField {
+        db: String,
+        collection: String,
+        path: String,
+    },
+    /// `db` is `None` when a new database is created along with its first collection.
+    CreateCollection {
+        uri: String,
+        db: Option<String>,
+    },
+    CreateIndex {
+        db: String,
+        collection: String,
+    },
+    AnalyzeSchema {
+        db: String,
+        collection: String,
+    },
+    CreateView {
+        uri: String,
+        db: String,
+        view_on: String,
+    },
+    /// Copies the source held in `AppState::pending_copy`.
+    CopyData {
+        target_uri: String,
+    },
+    CreateUser {
+        db: String,
+    },
+    /// Grants roles to `user`, or revokes them when `grant` is false.
+    SetRoles {
+        db: String,
+        user: String,
+        grant: bool,
+    },
+    ChangePassword {
+        db: String,
+        user: String,
+    },
+    FilterOperations {
+        paused: bool,
+    },
+    SetProfiling {
+        db: String,
+    },
+    ExportCollection {
+        db: String,
+        collection: String,
+    },
+    /// Column mapping for a CSV/TSV file; `remaining` files are mapped next.
+    ImportDelimited {
+        uri: String,
+        db: String,
+        path: PathBuf,
+        delimiter: char,
+        remaining: Vec<PathBuf>,
+    },
+}
+
+pub struct FormState {
+    pub title: String,
+    pub purpose: FormPurpose,
+    pub fields: Vec<FormField>,
+    pub selected: usize,
+    pub cursor_position: usize,
+    /// Read-only lines shown below the fields.
+    pub preview: Vec<String>,
+}
+
+impl FormState {
+    pub fn new(title: impl Into<String>, purpose: FormPurpose, fields: Vec<FormField>) -> Self {
+        let cursor_position = fields
+            .first()
+            .map(|field| field.value.chars().count())
+            .unwrap_or(0);
+        Self {
+            title: title.into(),
+            purpose,
+            fields,
+            selected: 0,
+            cursor_position,
+            preview: Vec::new(),
+        }
+    }
+
+    pub fn with_preview(mut self, preview: Vec<String>) -> Self {
+        self.preview = preview;
+        self
+    }
+
+    pub fn value(&self, label: &str) -> &str {
+        self.fields
+            .iter()
+            .find(|field| field.label == label)
+            .map(|field| field.value.as_str())
+            .unwrap_or("")
+    }
+
+    pub fn selected_field(&self) -> Option<&FormField> {
+        self.fields.get(self.selected)
+    }
+
+    fn select(&mut self, index: usize) {
+        self.selected = index;
+        self.cursor_position = self
+            .selected_field()
+            .map(|field| field.value.chars().count())
+            .unwrap_or(0);
+    }
+
+    pub fn next_field(&mut self) {
+        if !self.fields.is_empty() {
+            self.select((self.selected + 1) % self.fields.len());
+        }
+    }
+
+    pub fn previous_field(&mut self) {
+        if !self.fields.is_empty() {
+            self.select((self.selected + self.fields.len() - 1) % self.fields.len());
+        }
+    }
+
+    pub fn cycle_choice(&mut self, forward: bool) {
+        let Some(field) = self.fields.get_mut(self.selected) else {
+            return;
+        };
+        let FormFieldKind::Choice(options) = &field.kind else {
+            return;
+        };
+        let index = options
+            .iter()
+            .position(|option| *option == field.value)
+            .unwrap_or(0);
+        let next = if forward {
+            (index + 1) % options.len()
+        } else {
+            (index + options.len() - 1) % options.len()
+        };
+        field.value = options[next].clone();
+    }
+
+    pub fn insert_text(&mut self, text: &str) {
+        let Some(field) = self.fields.get_mut(self.selected) else {
+            return;
+        };
+        if matches!(field.kind, FormFieldKind::Choice(_)) {
+            return;
+        }
+        let mut chars: Vec<char> = field.value.chars().collect();
+        let position = self.cursor_position.min(chars.len());
+        for (offset, c) in text.chars().enumerate() {
+            chars.insert(position + offset, c);
+        }
+        field.value = chars.into_iter().collect();
+        self.cursor_position = position + text.chars().count();
+    }
+
+    pub fn backspace(&mut self) {
+        let Some(field) = self.fields.get_mut(self.selected) else {
+            return;
+        };
+        if matches!(field.kind, FormFieldKind::Choice(_)) || self.cursor_position == 0 {
+            return;
+        }
+        let mut chars: Vec<char> = field.value.chars().collect();
+        chars.remove(self.cursor_position - 1);
+        field.value = chars.into_iter().collect();
+        self.cursor_position -= 1;
+    }
+
+    pub fn move_cursor(&mut self, forward: bool) {
+        let len = self
+            .selected_field()
+            .map(|field| field.value.chars().count())
+            .unwrap_or(0);
+        if forward {
+            self.cursor_position = (self.cursor_position + 1).min(len);
+        } else {
+            self.cursor_position = self.cursor_position.saturating_sub(1);
+        }
+    }
+}
@@ -0,0 +1,720 @@
+use crate::app::{AppState, PendingBulkUpdate, WarnedWrite};
+use crate::db::csv::{ArrayMode, ColumnType};
+use crate::db::import_export::{
+    ExportFormat, ExportQuery, PreflightError, export_query, import_delimited,
+};
+use crate::db::staging::StagedChange;
+use crate::tui::form::{FormFieldKind, FormPurpose, FormState};
+use crate::value_type::ValueType;
+use crossterm::event::{KeyCode, KeyEvent};
+use mongodb::{
+    bson::{Document, doc},
+    options::{
+        ClusteredIndex, Collation, CreateCollectionOptions, TimeseriesGranularity,
+        TimeseriesOptions, UpdateModifications,
+    },
+};
+
+pub async fn handle_form_key(key: KeyEvent, state: &mut AppState) -> bool {
+    let Some(form) = &mut state.form else {
+        return true;
+    };
+    let is_choice = matches!(
+        form.selected_field().map(|field| &field.kind),
+        Some(FormFieldKind::Choice(_))
+    );
+
+    match key.code {
+        KeyCode::Esc => return true,
+        KeyCode::Tab | KeyCode::Down => form.next_field(),
+        KeyCode::BackTab | KeyCode::Up => form.previous_field(),
+        KeyCode::Left if is_choice => form.cycle_choice(false),
+        KeyCode::Right | KeyCode::Char(' ') if is_choice => form.cycle_choice(true),
+        KeyCode::Left => form.move_cursor(false),
+        KeyCode::Right => form.move_cursor(true),
+        KeyCode::Backspace => form.backspace(),
+        KeyCode::Char(c) => form.insert_text(&c.to_string()),
+        KeyCode::Enter => {
+            if let Some(form) = state.form.take() {
+                match submit_form(&form, state).await {
+                    // A submit may open a follow-up form, which stays open.
+                    Ok(()) => return state.form.is_none(),
+                    Err(error) => {
+                        state.popup_message = Some(format!("❌ {error}"));
+                        state.form = Some(form);
+                    }
+                }
+            }
+        }
+        _ => {}
+    }
+
+    false
+}
+
+fn collection_options(form: &FormState) -> Result<CreateCollectionOptions, String> {
+    let mut options = CreateCollectionOptions::default();
+
+    match form.value("Kind") {
+        "capped" => {
+            let size = form
+                .value("Capped size")
+                .trim()
+                .parse::<u64>()
+                .ok()
+                .filter(|size| *size > 0)
+                .ok_or_else(|| "Capped collections need a size in bytes.".to_string())?;
+            let max = form.value("Capped max").trim();
+            options.capped = Some(true);
+            options.size = Some(size);
+            if !max.is_empty() {
+                options.max = Some(
+                    max.parse::<u64>()
+                        .map_err(|_| format!("'{max}' is not a document count."))?,
+                );
+            }
+        }
+        "timeseries" => {
+            let time_field = form.value("Time field").trim();
+            if time_field.is_empty() {
+                return Err("Time-series collections need a time field.".to_string());
+            }
+            let meta_field = Some(form.value("Meta field").trim())
+                .filter(|field| !field.is_empty())
+                .map(str::to_string);
+            let granularity = match form.value("Granularity") {
+                "minutes" => TimeseriesGranularity::Minutes,
+                "hours" => TimeseriesGranularity::Hours,
+                _ => TimeseriesGranularity::Seconds,
+            };
+            options.timeseries = Some(
+                TimeseriesOptions::builder()
+                    .time_field(time_field)
+                    .meta_field(meta_field)
+                    .granularity(granularity)
+                    .build(),
+            );
+        }
+        "clustered" => options.clustered_index = Some(ClusteredIndex::default()),
+        _ => {}
+    }
+
+    let locale = form.value("Collation").trim();
+    if !locale.is_empty() {
+        options.collation = Some(Collation::builder().locale(locale).build());
+    }
+
+    let validator = form.value("Validator").trim();
+    if !validator.is_empty() {
+        options.validator = Some(
+            serde_json::from_str(validator)
+                .map_err(|error| format!("Validator is not a valid JSON object: {error}"))?,
+        );
+    }
+
+    Ok(options)
+}
+
+fn confirmed_password(form: &FormState) -> Result<&str, String> {
+    let password = form.value("Password");
+    if password.is_empty() {
+        return Err("Enter a password.".to_string());
+    }
+    if password != form.value("Confirm") {
+        return Err("Passwords do not match.".to_string());
+    }
+    Ok(password)
+}
+
+async fn submit_form(form: &FormState, state: &mut AppState) -> Result<(), String> {
+    let client = state
+        .mongo_client
+        .clone()
+        .ok_or_else(|| "No active MongoDB connection.".to_string())?;
+
+    match &form.purpose {
+        FormPurpose::EditField {
+            db,
+            collection,
+            id,
+            path,
+        } => {
+            let value_type = ValueType::parse(form.value("Type"))
+                .ok_or_else(|| "Unknown value type.".to_string())?;
+            let value = value_type.parse_value(form.value("Value"))?;
+
+            if let Some(original) = state
+                .current_documents
+                .iter()
+                .find(|doc| doc.get("_id") == Some(id))
+                .cloned()
+            {
+                let mut updated = original.clone();
+                updated.insert(path.clone(), value.clone());
+                let check = crate::db::validation::check_document(
+                    &client,
+                    db,
+                    collection,
+                    &updated,
+                    Some(&original),
+                )
+                .await
+                .map_err(|error| format!("Could not load collection validator: {error}"))?;
+                if !check.violations.is_empty() {
+                    let violations: Vec<String> =
+                        check.violations.iter().map(ToString::to_string).collect();
+                    if !check.warns_only {
+                        state.show_violations("Validation failed · field not saved", violations);
+                        return Ok(());
+                    }
+                    state.show_violations("Validator warning · field not saved yet", violations);
+                    state.confirm_warned_write(WarnedWrite::Update {
+                        db: db.clone(),
+                        collection: collection.clone(),
+                        original,
+                        edited: updated,
+                    });
+                    return Ok(());
+                }
+
+                if state.staged_changes.is_some() {
+                    state.stage(StagedChange::Update {
+                        db: db.clone(),
+                        collection: collection.clone(),
+                        original,
+                        edited: updated,
+                    });
+                    return Ok(());
+                }
+            }
+
+            crate::db::client::set_field_in_document(
+                &client,
+                db,
+                collection,
+                id.clone(),
+                path,
+                value.clone(),
+            )
+            .await
+            .map_err(|error| format!("Failed to update field: {error}"))?;
+
+            if let Some(doc) = state
+                .current_documents
+                .get_mut(state.selected_doc_index)
+                .filter(|doc| doc.get("_id") == Some(id))
+            {
+                doc.insert(path.clone(), value);
+            }
+            state.popup_message = None;
+            state.popup_message_success = Some(format!("✅ Updated field: {path}"));
+        }
+        FormPurpose::RefactorField {
+            db,
+            collection,
+            path,
+        } => {
+            let current_filter: Document = if state.filter_text.trim().is_empty() {
+                Document::new()
+            } else {
+                serde_json::from_str(&state.filter_text)
+                    .map_err(|error| format!("Filter is not valid JSON: {error}"))?
+            };
+            let exists = doc! { path.as_str(): { "$exists": true } };
+            let filter = if current_filter.is_empty() {
+                exists
+            } else {
+                doc! { "$and": [current_filter, exists] }
+            };
+
+            let mut conversion = None;
+            let update = match form.value("Operation") {
+                "rename" => {
+                    let new_name = form.value("New name").trim();
+                    if new_name.is_empty() || new_name == path || new_name.starts_with('$') {
+                        return Err("Enter a new field name for the rename.".to_string());
+                    }
+                    UpdateModifications::Document(doc! { "$rename": { path.as_str(): new_name } })
+                }
+                "convert" => {
+                    let to = form.value("Convert to").to_string();
+                    conversion = Some((path.clone(), to.clone()));
+                    UpdateModifications::Pipeline(crate::db::preview::convert_field_pipeline(
+                        path, &to,
+                    ))
+                }
+                _ => UpdateModifications::Document(doc! { "$unset": { path.as_str(): "" } }),
+            };
+
+            let matched_count = crate::db::client::count_documents_with_filter(
+                &client,
+                db,
+                collection,
+                filter.clone(),
+            )
+            .await
+            .map_err(|error| format!("Could not count matching documents: {error}"))?;
+
+            let pending = PendingBulkUpdate {
+                db: db.clone(),
+                collection: collection.clone(),
+                filter,
+                update,
+                matched_count,
+                conversion,
+            };
+            state.popup_message_success = None;
+            state.popup_message = Some(pending.confirmation_message());
+            state.pending_bulk_update = Some(pending);
+        }
+        FormPurpose::CreateIndex { db, collection } => {
+            let keys: Document = serde_json::from_str(form.value("Keys"))
+                .map_err(|error| format!("Keys are not a valid JSON object: {error}"))?;
+            if keys.is_empty() {
+                return Err("An index needs at least one key.".to_string());
+            }
+
+            let mut spec = doc! { "key": keys };
+            let name = form.value("Name").trim();
+            if !name.is_empty() {
+                spec.insert("name", name);
+            }
+            for option in ["Unique", "Sparse", "Hidden"] {
+                if form.value(option) == "yes" {
+                    spec.insert(option.to_lowercase(), true);
+                }
+            }
+            let ttl = form.value("TTL seconds").trim();
+            if !ttl.is_empty() {
+                let seconds = ttl
+                    .parse::<i64>()
+                    .map_err(|_| format!("'{ttl}' is not a number of seconds."))?;
+                spec.insert("expireAfterSeconds", seconds);
+            }
+            let partial = form.value("Partial filter").trim();
+            if !partial.is_empty() {
+                let filter: Document = serde_json::from_str(partial)
+                    .map_err(|error| format!("Partial filter is not valid JSON: {error}"))?;
+                spec.insert("partialFilterExpression", filter);
+            }
+            let locale = form.value("Collation").trim();
+            if !locale.is_empty() {
+                spec.insert("collation", doc! { "locale": locale });
+            }
+
+            let name = crate::db::indexes::create_index(&client, db, collection, spec)
+                .await
+                .map_err(|error| format!("Failed to create index: {error}"))?;
+            crate::tui::index_panel::open_index_panel(state, db, collection).await;
+            state.popup_message = None;
+            state.popup_message_success = Some(format!("✅ Created index: {name}"));
+        }
+        FormPurpose::AnalyzeSchema { db, collection } => {
+            let size = form
+                .value("Documents")
+                .trim()
+                .parse::<i64>()
+                .ok()
+                .filter(|size| *size > 0)
+                .ok_or_else(|| "Enter how many documents to analyze.".to_string())?;
+            let filter = if form.value("Source") == "current filter" {
+                let filter = if state.filter_text.trim().is_empty() {
+                    Document::new()
+                } else {
+                    serde_json::from_str(&state.filter_text)
+                        .map_err(|error| format!("Filter is not valid JSON: {error}"))?
+                };
+                Some(filter)
+            } else {
+                None
+            };
+            crate::tui::schema_panel::open_schema_panel(state, db, collection, filter, size)
+                .await?;
+        }
+        FormPurpose::ExportCollection { db, collection } => {
+            let parse = |label: &str| -> Result<Document, String> {
+                let text = form.value(label).trim();
+                if text.is_empty() {
+                    return Ok(Document::new());
+                }
+                serde_json::from_str(text)
+                    .map_err(|error| format!("{label} is not a valid JSON object: {error}"))
+            };
+            let pipeline = if form.value("Source") == "pipeline" {
+                let stages = serde_json::from_str::<Vec<Document>>(form.value("Pipeline"))
+                    .map_err(|error| format!("Pipeline is not a JSON array of stages: {error}"))?;
+                Some(stages)
+            } else {
+                None
+            };
+            let limit_text = form.value("Limit").trim();
+            let limit = if limit_text.is_empty() {
+                None
+            } else {
+                let limit = limit_text
+                    .parse::<i64>()
+                    .ok()
+                    .filter(|limit| *limit > 0)
+                    .ok_or_else(|| "Limit must be a positive number or empty.".to_string())?;
+                Some(limit)
+            };
+            let query = ExportQuery {
+                filter: parse("Filter")?,
+                sort: parse("Sort")?,
+                projection: parse("Projection")?,
+                pipeline,
+                limit,
+            };
+            let fields: Vec<String> = form
+                .value("Fields")
+                .split(',')
+                .map(str::trim)
+                .filter(|field| !field.is_empty())
+                .map(str::to_string)
+                .collect();
+            let arrays = if form.value("Arrays") == "index" {
+                ArrayMode::Index
+            } else {
+                ArrayMode::Join
+            };
+            let (format, extension) = match form.value("Format") {
+                "csv" => (
+                    ExportFormat::Delimited {
+                        delimiter: ',',
+                        fields,
+                        arrays,
+                    },
+                    "csv",
+                ),
+                "tsv" => (
+                    ExportFormat::Delimited {
+                        delimiter: '\t',
+                        fields,
+                        arrays,
+                    },
+                    "tsv",
+                ),
+                _ => (ExportFormat::JsonLines, "json"),
+            };
+
+            let file = form.value("File").trim();
+            let mut path = if file.is_empty() {
+                crate::utils::get_data_dir().join(format!("{db}_{collection}"))
+            } else {
+                std::path::PathBuf::from(file)
+            };
+            match path.extension().and_then(|ext| ext.to_str()) {
+                None => {
+                    path.set_extension(extension);
+                }
+                Some(other) if other != extension && ["json", "csv", "tsv"].contains(&other) => {
+                    return Err(format!(
+                        "{} ends in .{other} but the format is {extension}; change the file or the format.",
+                        path.display()
+                    ));
+                }
+                Some(_) => {}
+            }
+            if path.exists() && form.value("If file exists") != "overwrite" {
+                return Err(format!(
+                    "{} already exists; choose another file or set 'If file exists' to overwrite.",
+                    path.display()
+                ));
+            }
+            let exported = export_query(
+                &client,
+                db,
+                collection,
+                &path.to_string_lossy(),
+                &query,
+                &format,
+            )
+            .await
+            .map_err(|error| format!("Export failed: {error}"))?;
+            state.popup_message = None;
+            state.popup_message_success = Some(format!(
+                "✅ Exported {exported} document(s): {}",
+                path.display()
+            ));
+        }
+        FormPurpose::ImportDelimited {
+            uri,
+            db,
+            path,
+            delimiter,
+            remaining,
+        } => {
+            let collection = form.value("Collection").trim();
+            if collection.is_empty() {
+                return Err("Enter a collection name.".to_string());
+            }
+            // Every field after the collection maps one column, in file order.
+            let types: Vec<ColumnType> = form
+                .fields
+                .iter()
+                .skip(1)
+                .map(|field| ColumnType::from_label(&field.value))
+                .collect();
+
+            match import_delimited(
+                &client,
+                db,
+                collection,
+                &path.to_string_lossy(),
+                *delimiter,
+                &types,
+            )
+            .await
+            {
+                Ok(imported) => {
+                    state.popup_message = None;
+                    state.popup_message_success = Some(format!(
+                        "📥 Imported {imported} document(s) into {db}.{collection}"
+                    ));
+                    state.collection_to_load = Some((uri.clone(), db.clone()));
+                    crate::tui::csv_import::open_csv_import(state, uri, db, remaining.clone());
+                }
+                Err(error) => match error.downcast_ref::<PreflightError>() {
+                    Some(preflight) => {
+                        let title = format!("Import pre-flight · {} not imported", path.display());
+                        state.show_import_report(
+                            &title,
+                            preflight.violations.clone(),
+                            uri,
+                            db,
+                            remaining.clone(),
+                        );
+                        if let Some(documents) = &preflight.documents {
+                            state.confirm_warned_write(WarnedWrite::Import {
+                                db: db.clone(),
+                                batches: vec![(collection.to_string(), documents.clone())],
+                            });
+                        }
+                    }
+                    None => return Err(format!("Import failed: {error}")),
+                },
+            }
+        }
+        FormPurpose::CreateView { uri, db, view_on } => {
+            let name = form.value("Name").trim();
+            if name.is_empty() {
+                return Err("Enter a name for the view.".to_string());
+            }
+            let parse = |label: &str| -> Result<Document, String> {
+                let text = form.value(label).trim();
+                if text.is_empty() {
+                    return Ok(Document::new());
+                }
+                serde_json::from_str(text)
+                    .map_err(|error| format!("{label} is not a valid JSON object: {error}"))
+            };
+            let pipeline = if form.value("Source") == "pipeline" {
+                serde_json::from_str::<Vec<Document>>(form.value("Pipeline"))
+                    .map_err(|error| format!("Pipeline is not a JSON array of stages: {error}"))?
+            } else {
+                crate::db::views::find_pipeline(
+                    parse("Filter")?,
+                    parse("Sort")?,
+                    parse("Projection")?,
+                )
+            };
+
+            crate::db::views::create_view(&client, db, name, view_on, pipeline)
+                .await
+                .map_err(|error| format!("Failed to create view: {error}"))?;
+
+            state.collection_to_load = Some((uri.clone(), db.clone()));
+            state.popup_message = None;
+            state.popup_message_success = Some(format!("✅ Created view: {db}.{name}"));
+        }
+        FormPurpose::CopyData { target_uri } => {
+            let Some(copy) = &state.pending_copy else {
+                return Err("Nothing to copy.".to_string());
+            };
+            let target_db = form.value("Target database").trim();
+            if target_db.is_empty() {
+                return Err("Enter a target database.".to_string());
+            }
+            let filter_text = form.value("Filter").trim();
+            let filter: Document = if filter_text.is_empty() {
+                Document::new()
+            } else {
+                serde_json::from_str(filter_text)
+                    .map_err(|error| format!("Filter is not valid JSON: {error}"))?
+            };
+
+            let collections = match &copy.collection {
+                Some(collection) => {
+                    let target = form.value("Target collection").trim();
+                    if target.is_empty() {
+                        return Err("Enter a target collection.".to_string());
+                    }
+                    vec![(collection.clone(), target.to_string())]
+                }
+                None => {
+                    let views = crate::db::views::list_views(&copy.source, &copy.db)
+                        .await
+                        .map_err(|error| format!("Could not list views: {error}"))?;
+                    crate::db::client::list_collections(&copy.source, &copy.db)
+                        .await
+                        .map_err(|error| format!("Could not list collections: {error}"))?
+                        .into_iter()
+                        .filter(|name| !name.starts_with("system.") && !views.contains_key(name))
+                        .map(|name| (name.clone(), name))
+                        .collect()
+                }
+            };
+            if crate::utils::server_key(target_uri) == crate::utils::server_key(&copy.uri)
+                && target_db == copy.db
+                && collections.iter().any(|(source, target)| source == target)
+            {
+                return Err("Source and target are the same.".to_string());
+            }
+            if form.value("At target") == "drop" {
+                let dropped = match &copy.collection {
+                    Some(_) => format!("{target_db}.{}", collections[0].1),
+                    None => target_db.to_string(),
+                };
+                if form.value("Confirm drop").trim() != dropped {
+                    return Err(format!(
+                        "Type '{dropped}' in Confirm drop to drop the target before copying."
+                    ));
+                }
+            }
+
+            let target = if state.connected_uri.as_ref() == Some(target_uri) {
+                client
+            } else {
+                let resolved = crate::utils::resolve_connection_uri_by_stored_uri(
+                    target_uri,
+                    &state.connections,
+                )
+                .map_err(|error| format!("Connection password unavailable: {error}"))?;
+                crate::db::client::connect_to_uri(&resolved)
+                    .await
+                    .map_err(|error| format!("Could not connect to target: {error}"))?
+            };
+            let options = crate::db::copy::CopyOptions {
+                filter,
+                copy_indexes: form.value("Indexes") == "copy",
+                mode: if form.value("At target") == "drop" {
+                    crate::db::copy::CopyMode::Drop
+                } else {
+                    crate::db::copy::CopyMode::Merge
+                },
+            };
+
+            let job = crate::db::copy::CopyJob::new(
+                copy.source.clone(),
+                &copy.db,
+                target,
+                target_uri,
+                target_db,
+                collections,
+                options,
+            )
+            .await
+            .map_err(|error| format!("Could not start copy: {error}"))?;
+            state.pending_copy = None;
+            state.popup_message = None;
+            state.popup_message_success = None;
+            state.copy_job = Some(job);
+        }
+        FormPurpose::CreateUser { db } => {
+            let user = form.value("User").trim();
+            if user.is_empty() {
+                return Err("Enter a user name.".to_string());
+            }
+            let password = confirmed_password(form)?;
+            let roles = crate::db::users::parse_roles(form.value("Roles"), db)?;
+
+            crate::db::users::create_user(&client, db, user, password, &roles)
+                .await
+                .map_err(|error| format!("Failed to create user: {error}"))?;
+            crate::tui::users_panel::open_users_panel(state, db).await;
+            state.popup_message_success = Some(format!("✅ Created user: {user}"));
+        }
+        FormPurpose::SetRoles { db, user, grant } => {
+            let roles = crate::db::users::parse_roles(form.value("Roles"), db)?;
+            if roles.is_empty() {
+                return Err("Enter at least one role.".to_string());
+            }
+
+            crate::db::users::set_roles(&client, db, user, &roles, *grant)
+                .await
+                .map_err(|error| format!("Failed to change roles: {error}"))?;
+            crate::tui::users_panel::open_users_panel(state, db).await;
+            let action = if *grant { "Granted" } else { "Revoked" };
+            state.popup_message_success = Some(format!("✅ {action} roles for {user}"));
+        }
+        FormPurpose::ChangePassword { db, user } => {
+            let password = confirmed_password(form)?;
+
+            crate::db::users::change_password(&client, db, user, password)
+                .await
+                .map_err(|error| format!("Failed to change password: {error}"))?;
+            state.popup_message = None;
+            state.popup_message_success = Some(format!("✅ Changed password of {user}"));
+        }
+        FormPurpose::FilterOperations { paused } => {
+            let min_ms = form.value("Min ms").trim();
+            let filter = crate::db::ops::OperationFilter {
+                namespace: form.value("Namespace").trim().to_string(),
+                min_ms: if min_ms.is_empty() {
+                    0
+                } else {
+                    min_ms
+                        .parse::<i64>()
+                        .map_err(|_| format!("'{min_ms}' is not a number of milliseconds."))?
+                },
+            };
+            crate::tui::ops_panel::open_ops_panel(state, filter, *paused).await;
+        }
+        FormPurpose::SetProfiling { db } => {
+            let level = crate::db::profiler::PROFILE_LEVELS
+                .iter()
+                .position(|level| *level == form.value("Level"))
+                .unwrap_or(0) as i32;
+            let slowms_text = form.value("Slow ms").trim();
+            let slowms = slowms_text
+                .parse::<i64>()
+                .map_err(|_| format!("'{slowms_text}' is not a number of milliseconds."))?;
+
+            crate::db::profiler::set_profile(&client, db, level, slowms)
+                .await
+                .map_err(|error| format!("Failed to set profiling level: {error}"))?;
+            crate::tui::profiler_panel::open_profiler_panel(state, db).await;
+            state.popup_message = None;
+            state.popup_message_success = Some(format!(
+                "✅ Profiling for {db}: {} (slowms {slowms})",
+                form.value("Level")
+            ));
+        }
+        FormPurpose::CreateCollection { uri, db } => {
+            let db = match db {
+                Some(db) => db.clone(),
+                None => form.value("Database").trim().to_string(),
+            };
+            let name = form.value("Collection").trim();
+            if db.is_empty() || name.is_empty() {
+                return Err("Database and collection names are required.".to_string());
+            }
+            let options = collection_options(form)?;
+
+            crate::db::client::create_collection(&client, &db, name, options)
+                .await
+                .map_err(|error| format!("Failed to create collection: {error}"))?;
+
+            let databases = state.database_map.entry(uri.clone()).or_default();
+            if !databases.contains(&db) {
+                databases.push(db.clone());
+            }
+            state.expanded_uris.insert(uri.clone());
+            state.collection_to_load = Some((uri.clone(), db.clone()));
+            state.popup_message = None;
+            state.popup_message_success = Some(format!("✅ Created collection: {db}.{name}"));
+        }
+    }
+
+    Ok(())
+}
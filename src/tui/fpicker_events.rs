@@ -1,7 +1,9 @@
-use crate::app::{AppState, SelectableItem};
+use crate::app::{AppState, SelectableItem, WarnedWrite};
 use crate::db::import_export::import_database;
-use crate::tui::filepicker::{FileEntry, FilePickerMode};
+use crate::tui::csv_import::{delimiter_for, open_csv_import};
+use crate::tui::filepicker::{FileEntry, FilePickerMode, ImportSummary};
 use crossterm::event::{KeyCode, KeyEvent};
+use std::path::PathBuf;
 
 pub async fn handle_filepicker_key(event: KeyEvent, state: &mut AppState) -> bool {
     match event.code {
@@ -54,13 +56,44 @@ pub async fn handle_filepicker_key(event: KeyEvent, state: &mut AppState) -> boo
                         return false;
                     };
 
-                    let (success, failed) = picker.perform_import(client, db_name, uri).await;
+                    let ImportSummary {
+                        success,
+                        failed,
+                        report,
+                        warned,
+                    } = picker.perform_import(client, db_name, uri).await;
                     state.popup_message_success = Some(format!(
                         "📥 Imported: {} ✅ | ❌ Failed: {}",
                         success, failed
                     ));
 
+                    let mut delimited: Vec<PathBuf> = picker
+                        .selected_files
+                        .iter()
+                        .filter(|path| delimiter_for(path).is_some())
+                        .cloned()
+                        .collect();
+                    delimited.sort();
+
+                    let (uri, db_name) = (uri.clone(), db_name.clone());
                     state.collection_to_load = Some((uri.clone(), db_name.clone()));
+                    if !report.is_empty() {
+                        state.show_import_report(
+                            "Import pre-flight · files not imported",
+                            report,
+                            &uri,
+                            &db_name,
+                            delimited,
+                        );
+                        if !warned.is_empty() {
+                            state.confirm_warned_write(WarnedWrite::Import {
+                                db: db_name,
+                                batches: warned,
+                            });
+                        }
+                    } else if !delimited.is_empty() {
+                        open_csv_import(state, &uri, &db_name, delimited);
+                    }
                 }
 
                 FilePickerMode::ImportDatabase => {
@@ -0,0 +1,264 @@
+use crate::app::{AppState, InfoPanel, PanelKind, PanelLine, PanelTone, PendingDeletion};
+use crate::db::indexes::{
+    ID_INDEX, IndexUsage, LOW_ACCESS_OPS, MIN_STATS_DAYS, create_index, index_usage,
+    list_index_details, redundant_indexes, set_index_hidden,
+};
+use crate::keybindings::editor::open_index_spec_editor;
+use crate::tui::form::{FormField, FormPurpose, FormState};
+use crate::utils::format_bytes;
+use crossterm::event::{KeyCode, KeyEvent};
+use mongodb::bson::DateTime;
+
+pub async fn open_index_panel(state: &mut AppState, db: &str, collection: &str) {
+    let Some(client) = state.mongo_client.clone() else {
+        state.popup_message = Some("❌ No active MongoDB connection.".to_string());
+        return;
+    };
+
+    let indexes = match list_index_details(&client, db, collection).await {
+        Ok(indexes) => indexes,
+        Err(error) => {
+            state.popup_message = Some(format!("❌ Could not list indexes: {error}"));
+            return;
+        }
+    };
+
+    let mut lines = Vec::new();
+    let mut items = Vec::new();
+    for index in &indexes {
+        items.push(lines.len());
+        let tone = if index.hidden() {
+            PanelTone::Muted
+        } else {
+            PanelTone::Normal
+        };
+        lines.push(PanelLine::new(
+            tone,
+            format!("{}  {}", index.name, index.keys),
+        ));
+
+        let mut details = index.option_labels();
+        if let Some(size) = index.size {
+            details.push(format_bytes(size));
+        }
+        if !details.is_empty() {
+            lines.push(PanelLine::new(
+                PanelTone::Muted,
+                format!("    {}", details.join(" · ")),
+            ));
+        }
+    }
+    if indexes.is_empty() {
+        lines.push(PanelLine::new(PanelTone::Muted, "No indexes."));
+    }
+
+    let selected = match &state.info_panel {
+        Some(InfoPanel {
+            kind: PanelKind::Indexes { .. },
+            selected,
+            ..
+        }) => (*selected).min(items.len().saturating_sub(1)),
+        _ => 0,
+    };
+    let mut panel = InfoPanel::selectable(
+        format!(
+            "Indexes: {db}.{collection} · a: add · e: add as JSON · d: drop · h: hide/unhide · r: refresh · Esc: close"
+        ),
+        lines,
+        PanelKind::Indexes {
+            db: db.to_string(),
+            collection: collection.to_string(),
+            indexes,
+        },
+        items,
+    );
+    panel.selected = selected;
+    state.info_panel = Some(panel);
+}
+
+/// Reports unused, rarely used and prefix-redundant indexes for each of the
+/// given collections.
+pub async fn open_index_report(state: &mut AppState, db: &str, collections: Vec<String>) {
+    let Some(client) = state.mongo_client.clone() else {
+        state.popup_message = Some("❌ No active MongoDB connection.".to_string());
+        return;
+    };
+
+    let mut lines = Vec::new();
+    for collection in &collections {
+        lines.push(PanelLine::new(
+            PanelTone::Header,
+            format!("{db}.{collection}"),
+        ));
+
+        let indexes = match list_index_details(&client, db, collection).await {
+            Ok(indexes) => indexes,
+            Err(error) => {
+                lines.push(PanelLine::new(
+                    PanelTone::Muted,
+                    format!("  skipped: {error}"),
+                ));
+                lines.push(PanelLine::new(PanelTone::Normal, ""));
+                continue;
+            }
+        };
+        let usage = match index_usage(&client, db, collection).await {
+            Ok(usage) => usage,
+            Err(error) => {
+                lines.push(PanelLine::new(
+                    PanelTone::Muted,
+                    format!("  $indexStats unavailable: {error}"),
+                ));
+                Vec::new()
+            }
+        };
+
+        let before = lines.len();
+        let now = DateTime::now();
+        let (judged, recent): (Vec<&IndexUsage>, Vec<&IndexUsage>) = usage
+            .iter()
+            .filter(|entry| entry.name != ID_INDEX && entry.ops <= LOW_ACCESS_OPS)
+            .partition(|entry| entry.has_mature_stats(now));
+        for entry in judged {
+            let since = entry
+                .since
+                .and_then(|since| since.try_to_rfc3339_string().ok())
+                .unwrap_or_else(|| "?".to_string());
+            let (label, tone) = if entry.ops == 0 {
+                ("unused", PanelTone::Removed)
+            } else {
+                ("rarely used", PanelTone::Normal)
+            };
+            lines.push(PanelLine::new(
+                tone,
+                format!(
+                    "  {label}: {} ({} ops since {since})",
+                    entry.name, entry.ops
+                ),
+            ));
+        }
+        if !recent.is_empty() {
+            let names: Vec<&str> = recent.iter().map(|entry| entry.name.as_str()).collect();
+            lines.push(PanelLine::new(
+                PanelTone::Muted,
+                format!(
+                    "  not judged, stats cover under {MIN_STATS_DAYS} days: {}",
+                    names.join(", ")
+                ),
+            ));
+        }
+        for (redundant, covering) in redundant_indexes(&indexes) {
+            lines.push(PanelLine::new(
+                PanelTone::Removed,
+                format!("  redundant: {redundant} is a prefix of {covering}"),
+            ));
+        }
+
+        if lines.len() == before {
+            lines.push(PanelLine::new(PanelTone::Added, "  ✓ no findings"));
+        }
+        lines.push(PanelLine::new(PanelTone::Normal, ""));
+    }
+
+    let scope = match collections.as_slice() {
+        [collection] => format!("{db}.{collection}"),
+        _ => db.to_string(),
+    };
+    state.popup_message = None;
+    state.info_panel = Some(InfoPanel::new(
+        format!(
+            "Index report: {scope} · low use means ≤ {LOW_ACCESS_OPS} ops in {MIN_STATS_DAYS}+ days · Esc: close"
+        ),
+        lines,
+    ));
+}
+
+pub async fn handle_index_panel_key(key: KeyEvent, state: &mut AppState) {
+    let Some(InfoPanel {
+        kind:
+            PanelKind::Indexes {
+                db,
+                collection,
+                indexes,
+            },
+        selected,
+        ..
+    }) = &state.info_panel
+    else {
+        return;
+    };
+    let (db, collection) = (db.clone(), collection.clone());
+    let index = indexes
+        .get(*selected)
+        .map(|index| (index.name.clone(), index.hidden()));
+
+    match key.code {
+        KeyCode::Char('r') => open_index_panel(state, &db, &collection).await,
+        KeyCode::Char('a') => {
+            state.form = Some(FormState::new(
+                format!("New index on {db}.{collection}"),
+                FormPurpose::CreateIndex { db, collection },
+                vec![
+                    FormField::text("Keys", r#"{"field": 1}"#),
+                    FormField::text("Name", ""),
+                    FormField::choice("Unique", &["no", "yes"], "no"),
+                    FormField::choice("Sparse", &["no", "yes"], "no"),
+                    FormField::choice("Hidden", &["no", "yes"], "no"),
+                    FormField::text("TTL seconds", ""),
+                    FormField::text("Partial filter", ""),
+                    FormField::text("Collation", ""),
+                ],
+            ));
+        }
+        KeyCode::Char('e') => match open_index_spec_editor(state) {
+            Ok(Some(spec)) => {
+                let Some(client) = state.mongo_client.clone() else {
+                    return;
+                };
+                match create_index(&client, &db, &collection, spec).await {
+                    Ok(name) => {
+                        open_index_panel(state, &db, &collection).await;
+                        state.popup_message_success = Some(format!("✅ Created index: {name}"));
+                    }
+                    Err(error) => {
+                        state.popup_message = Some(format!("❌ Failed to create index: {error}"))
+                    }
+                }
+            }
+            Ok(None) => {}
+            Err(error) => state.popup_message = Some(format!("❌ {error}")),
+        },
+        KeyCode::Char('d') | KeyCode::Char('h') if index.is_none() => {}
+        KeyCode::Char('d') | KeyCode::Char('h')
+            if index.as_ref().is_some_and(|(name, _)| name == ID_INDEX) =>
+        {
+            state.popup_message = Some("❌ The _id index cannot be dropped or hidden.".to_string());
+        }
+        KeyCode::Char('d') => {
+            let deletion = PendingDeletion::Index {
+                db,
+                collection,
+                name: index.map(|(name, _)| name).unwrap_or_default(),
+            };
+            state.popup_message_success = None;
+            state.popup_message = Some(deletion.confirmation_message());
+            state.pending_deletion = Some(deletion);
+        }
+        KeyCode::Char('h') => {
+            let Some(((name, hidden), client)) = index.zip(state.mongo_client.clone()) else {
+                return;
+            };
+            match set_index_hidden(&client, &db, &collection, &name, !hidden).await {
+                Ok(()) => {
+                    open_index_panel(state, &db, &collection).await;
+                    let action = if hidden { "Unhid" } else { "Hid" };
+                    state.popup_message_success = Some(format!("✅ {action} index: {name}"));
+                }
+                Err(error) => {
+                    state.popup_message = Some(format!("❌ Failed to change index: {error}"))
+                }
+            }
+        }
+        _ => {}
+    }
+}
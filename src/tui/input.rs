@@ -25,7 +25,9 @@ pub fn render_input(f: &mut Frame, area: Rect, state: &mut AppState) {
         InputContext::Uri => "Enter Mongo URI",
         InputContext::ConnectionName => "Edit Connection Name",
         InputContext::CollectionName => "Rename Collection",
+        InputContext::DatabaseName => "Rename Database",
         InputContext::SearchCollections => "Search Collections",
+        InputContext::CloneTargetCollection => "Target Collection Name",
         InputContext::None => unreachable!(),
     };
 
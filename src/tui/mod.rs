@@ -1,4 +1,16 @@
+pub mod csv_import;
+pub mod dashboard;
 pub mod events;
 pub mod filepicker;
+pub mod form;
+pub mod form_events;
 pub mod fpicker_events;
+pub mod index_panel;
 pub mod input;
+pub mod ops_panel;
+pub mod panel_events;
+pub mod profiler_panel;
+pub mod replset_panel;
+pub mod schema_panel;
+pub mod stats_panel;
+pub mod users_panel;
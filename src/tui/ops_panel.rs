@@ -0,0 +1,175 @@
+use crate::app::{AppState, InfoPanel, PanelKind, PanelLine, PanelTone, PendingKillOp};
+use crate::db::ops::{OperationFilter, current_ops};
+use crate::tui::form::{FormField, FormPurpose, FormState};
+use crossterm::event::{KeyCode, KeyEvent};
+use std::time::{Duration, Instant};
+
+pub const OPS_REFRESH_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Lists active operations from `currentOp`, keeping the selected op when it
+/// is still running.
+pub async fn open_ops_panel(state: &mut AppState, filter: OperationFilter, paused: bool) {
+    let Some(client) = state.mongo_client.clone() else {
+        state.popup_message = Some("❌ No active MongoDB connection.".to_string());
+        return;
+    };
+
+    let operations = match current_ops(&client, &filter).await {
+        Ok(operations) => operations,
+        Err(error) => {
+            state.popup_message = Some(format!("❌ Could not load currentOp: {error}"));
+            // Wait a full interval before retrying instead of on every tick.
+            if let Some(InfoPanel {
+                kind: PanelKind::Operations { refreshed_at, .. },
+                ..
+            }) = &mut state.info_panel
+            {
+                *refreshed_at = Instant::now();
+            }
+            return;
+        }
+    };
+
+    let mut lines = vec![PanelLine::new(
+        PanelTone::Header,
+        format!(
+            "{:<12} {:<10} {:>9}  {:<30} {:<22} query",
+            "opid", "op", "ms", "namespace", "client"
+        ),
+    )];
+    let mut items = Vec::new();
+    for operation in &operations {
+        items.push(lines.len());
+        lines.push(PanelLine::new(
+            PanelTone::Normal,
+            format!(
+                "{:<12} {:<10} {:>9}  {:<30} {:<22} {}",
+                operation.opid.to_string(),
+                operation.op,
+                operation.running_ms,
+                operation.ns,
+                operation.client,
+                operation.summary
+            ),
+        ));
+    }
+    if operations.is_empty() {
+        lines.push(PanelLine::new(PanelTone::Muted, "No matching operations."));
+    }
+
+    let selected = match &state.info_panel {
+        Some(InfoPanel {
+            kind: PanelKind::Operations { opids, .. },
+            selected,
+            ..
+        }) => opids
+            .get(*selected)
+            .and_then(|opid| operations.iter().position(|op| op.opid == *opid))
+            .unwrap_or(0),
+        _ => 0,
+    };
+
+    let mut scope = Vec::new();
+    if !filter.namespace.is_empty() {
+        scope.push(format!("ns ~ {}", filter.namespace));
+    }
+    if filter.min_ms > 0 {
+        scope.push(format!("≥ {} ms", filter.min_ms));
+    }
+    let refresh = if paused { "paused" } else { "auto-refresh" };
+    let title = format!(
+        "currentOp: {} op(s){} · {refresh} · x: kill · f: filter · p: pause · r: refresh · Esc: close",
+        operations.len(),
+        if scope.is_empty() {
+            String::new()
+        } else {
+            format!(" ({})", scope.join(", "))
+        }
+    );
+
+    let mut panel = InfoPanel::selectable(
+        title,
+        lines,
+        PanelKind::Operations {
+            filter,
+            opids: operations.iter().map(|op| op.opid.clone()).collect(),
+            namespaces: operations.into_iter().map(|op| op.ns).collect(),
+            paused,
+            refreshed_at: Instant::now(),
+        },
+        items,
+    );
+    panel.selected = selected;
+    state.info_panel = Some(panel);
+}
+
+/// Called from the main loop; reloads the operations panel when it is due.
+pub async fn refresh_ops_panel_if_due(state: &mut AppState) {
+    let Some(InfoPanel {
+        kind:
+            PanelKind::Operations {
+                filter,
+                paused: false,
+                refreshed_at,
+                ..
+            },
+        ..
+    }) = &state.info_panel
+    else {
+        return;
+    };
+    if refreshed_at.elapsed() < OPS_REFRESH_INTERVAL || state.pending_kill_op.is_some() {
+        return;
+    }
+    let filter = filter.clone();
+    open_ops_panel(state, filter, false).await;
+}
+
+pub async fn handle_ops_panel_key(key: KeyEvent, state: &mut AppState) {
+    let Some(InfoPanel {
+        kind:
+            PanelKind::Operations {
+                filter,
+                opids,
+                namespaces,
+                paused,
+                ..
+            },
+        selected,
+        ..
+    }) = &state.info_panel
+    else {
+        return;
+    };
+    let (filter, paused) = (filter.clone(), *paused);
+    let target = opids
+        .get(*selected)
+        .cloned()
+        .zip(namespaces.get(*selected).cloned());
+
+    match key.code {
+        KeyCode::Char('r') => open_ops_panel(state, filter, paused).await,
+        KeyCode::Char('p') => open_ops_panel(state, filter, !paused).await,
+        KeyCode::Char('f') => {
+            state.form = Some(FormState::new(
+                "Filter operations",
+                FormPurpose::FilterOperations { paused },
+                vec![
+                    FormField::text("Namespace", filter.namespace),
+                    FormField::text("Min ms", filter.min_ms.to_string()),
+                ],
+            ));
+        }
+        KeyCode::Char('x') => {
+            let Some((opid, ns)) = target else {
+                state.popup_message = Some("❗ Select an operation.".to_string());
+                return;
+            };
+            let kill = PendingKillOp { opid, ns };
+            state.popup_message_success = None;
+            state.popup_message = Some(kill.confirmation_message());
+            state.pending_kill_op = Some(kill);
+        }
+        _ => {}
+    }
+}
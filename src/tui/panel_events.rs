@@ -0,0 +1,59 @@
+use crate::app::{AppState, InfoPanel, PanelKind};
+use crate::tui::csv_import::open_csv_import;
+use crate::tui::index_panel::handle_index_panel_key;
+use crate::tui::ops_panel::handle_ops_panel_key;
+use crate::tui::profiler_panel::handle_profiler_panel_key;
+use crate::tui::schema_panel::handle_schema_panel_key;
+use crate::tui::users_panel::handle_users_panel_key;
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+/// Handles a key while an info panel is open. Returns `false` when the key
+/// should fall through to a pending confirmation instead.
+pub async fn handle_panel_key(key: KeyEvent, state: &mut AppState) -> bool {
+    let awaiting_confirmation = state.pending_confirmation_message().is_some();
+    let Some(panel) = &mut state.info_panel else {
+        return false;
+    };
+
+    let selectable = !panel.items.is_empty();
+    match key.code {
+        KeyCode::Char('j') | KeyCode::Down if selectable => panel.select_next(),
+        KeyCode::Char('k') | KeyCode::Up if selectable => panel.select_previous(),
+        KeyCode::Char('j') | KeyCode::Down => panel.scroll_down(1),
+        KeyCode::Char('k') | KeyCode::Up => panel.scroll_up(1),
+        KeyCode::PageDown => panel.scroll_down(10),
+        KeyCode::PageUp => panel.scroll_up(10),
+        KeyCode::Char('d') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+            panel.scroll_down(10)
+        }
+        KeyCode::Char('u') if key.modifiers.contains(KeyModifiers::CONTROL) => panel.scroll_up(10),
+        // Closing the violations of a warned write answers it.
+        KeyCode::Esc | KeyCode::Char('q') if state.pending_warned_write.is_some() => return false,
+        KeyCode::Esc | KeyCode::Char('q') => {
+            close_info_panel(state);
+            state.popup_message = state.pending_confirmation_message();
+        }
+        _ if awaiting_confirmation => return false,
+        _ => match panel.kind {
+            PanelKind::Indexes { .. } => handle_index_panel_key(key, state).await,
+            PanelKind::Schema { .. } => handle_schema_panel_key(key, state),
+            PanelKind::Operations { .. } => handle_ops_panel_key(key, state).await,
+            PanelKind::Profile { .. } => handle_profiler_panel_key(key, state).await,
+            PanelKind::Users { .. } => handle_users_panel_key(key, state).await,
+            PanelKind::Info | PanelKind::ImportReport { .. } => {}
+        },
+    }
+
+    true
+}
+
+/// Closes the info panel; an import report goes on to map its CSV/TSV files.
+pub fn close_info_panel(state: &mut AppState) {
+    if let Some(InfoPanel {
+        kind: PanelKind::ImportReport { uri, db, files },
+        ..
+    }) = state.info_panel.take()
+    {
+        open_csv_import(state, &uri, &db, files);
+    }
+}
@@ -0,0 +1,225 @@
+use crate::app::{AppState, FocusArea, InfoPanel, PanelKind, PanelLine, PanelTone};
+use crate::db::profiler::{
+    PROFILE_ENTRY_LIMIT, PROFILE_LEVELS, ProfileEntry, ProfileSort, ProfileStatus, group_by_shape,
+    load_profile, profile_status, sort_entries,
+};
+use crate::tui::form::{FormField, FormPurpose, FormState};
+use crossterm::event::{KeyCode, KeyEvent};
+use mongodb::bson::doc;
+
+fn build_panel(
+    db: String,
+    status: ProfileStatus,
+    mut entries: Vec<ProfileEntry>,
+    sort: ProfileSort,
+    grouped: bool,
+) -> InfoPanel {
+    sort_entries(&mut entries, sort);
+    let level = PROFILE_LEVELS
+        .get(status.level as usize)
+        .copied()
+        .unwrap_or("?");
+    let header = format!(
+        "level {level} · slowms {} · {} entr(ies)",
+        status.slowms,
+        entries.len()
+    );
+
+    let mut lines = vec![PanelLine::new(PanelTone::Muted, header)];
+    let mut items = Vec::new();
+    if grouped {
+        lines.push(PanelLine::new(
+            PanelTone::Header,
+            format!(
+                "{:>6} {:>9} {:>9} {:>12}  shape",
+                "count", "total ms", "max ms", "docsExamined"
+            ),
+        ));
+        for group in group_by_shape(&entries) {
+            lines.push(PanelLine::new(
+                PanelTone::Normal,
+                format!(
+                    "{:>6} {:>9} {:>9} {:>12}  {}",
+                    group.count, group.total_ms, group.max_ms, group.docs_examined, group.shape
+                ),
+            ));
+        }
+    } else {
+        lines.push(PanelLine::new(
+            PanelTone::Header,
+            format!(
+                "{:>7}  {:<10} {:<30} {:<20} {:>15} {:>8}",
+                "ms", "op", "namespace", "plan", "docsEx/nret", "keysEx"
+            ),
+        ));
+        for entry in &entries {
+            items.push(lines.len());
+            lines.push(PanelLine::new(
+                PanelTone::Normal,
+                format!(
+                    "{:>7}  {:<10} {:<30} {:<20} {:>15} {:>8}",
+                    entry.millis,
+                    entry.op,
+                    entry.ns,
+                    entry.plan_summary,
+                    format!("{}/{}", entry.docs_examined, entry.nreturned),
+                    entry.keys_examined
+                ),
+            ));
+        }
+    }
+    if entries.is_empty() {
+        lines.push(PanelLine::new(
+            PanelTone::Muted,
+            "system.profile is empty. Press l to turn profiling on.",
+        ));
+    }
+
+    let view = if grouped {
+        "grouped by shape".to_string()
+    } else {
+        format!("sorted by {}", sort.label())
+    };
+    InfoPanel::selectable(
+        format!(
+            "Profiler: {db} · {view} · s: sort · g: group · Enter: open · l: level · r: refresh · Esc: close"
+        ),
+        lines,
+        PanelKind::Profile {
+            db,
+            status,
+            entries,
+            sort,
+            grouped,
+        },
+        items,
+    )
+}
+
+/// Shows the newest `system.profile` entries of a database with its
+/// profiling level.
+pub async fn open_profiler_panel(state: &mut AppState, db: &str) {
+    let Some(client) = state.mongo_client.clone() else {
+        state.popup_message = Some("❌ No active MongoDB connection.".to_string());
+        return;
+    };
+
+    let status = match profile_status(&client, db).await {
+        Ok(status) => status,
+        Err(error) => {
+            state.popup_message = Some(format!("❌ Could not read profiling level: {error}"));
+            return;
+        }
+    };
+    let entries = match load_profile(&client, db).await {
+        Ok(entries) => entries,
+        Err(error) => {
+            state.popup_message = Some(format!("❌ Could not read system.profile: {error}"));
+            return;
+        }
+    };
+
+    let (sort, grouped) = match &state.info_panel {
+        Some(InfoPanel {
+            kind: PanelKind::Profile { sort, grouped, .. },
+            ..
+        }) => (*sort, *grouped),
+        _ => (ProfileSort::Newest, false),
+    };
+    state.info_panel = Some(build_panel(db.to_string(), status, entries, sort, grouped));
+}
+
+pub async fn handle_profiler_panel_key(key: KeyEvent, state: &mut AppState) {
+    let Some(InfoPanel {
+        kind:
+            PanelKind::Profile {
+                db,
+                status,
+                sort,
+                grouped,
+                ..
+            },
+        ..
+    }) = &state.info_panel
+    else {
+        return;
+    };
+    let (db, level, slowms, sort, grouped) =
+        (db.clone(), status.level, status.slowms, *sort, *grouped);
+
+    match key.code {
+        KeyCode::Char('r') => open_profiler_panel(state, &db).await,
+        KeyCode::Char('s') | KeyCode::Char('g') => {
+            let Some(InfoPanel {
+                kind:
+                    PanelKind::Profile {
+                        db,
+                        status,
+                        entries,
+                        ..
+                    },
+                ..
+            }) = state.info_panel.take()
+            else {
+                return;
+            };
+            let (sort, grouped) = match key.code {
+                KeyCode::Char('s') => (sort.next(), false),
+                _ => (sort, !grouped),
+            };
+            state.info_panel = Some(build_panel(db, status, entries, sort, grouped));
+        }
+        KeyCode::Char('l') => {
+            let level = PROFILE_LEVELS.get(level as usize).copied().unwrap_or("off");
+            state.form = Some(FormState::new(
+                format!("Profiling for {db}"),
+                FormPurpose::SetProfiling { db },
+                vec![
+                    FormField::choice("Level", &PROFILE_LEVELS, level),
+                    FormField::text("Slow ms", slowms.to_string()),
+                ],
+            ));
+        }
+        KeyCode::Enter if !grouped => open_selected_entry(state),
+        _ => {}
+    }
+}
+
+/// Loads the selected entry in the document view by filtering
+/// `system.profile` on its timestamp.
+fn open_selected_entry(state: &mut AppState) {
+    let Some(InfoPanel {
+        kind: PanelKind::Profile { db, entries, .. },
+        selected,
+        ..
+    }) = &state.info_panel
+    else {
+        return;
+    };
+    let Some((entry, uri)) = entries.get(*selected).zip(state.connected_uri.clone()) else {
+        return;
+    };
+    let Some(ts) = entry.ts else {
+        state.popup_message = Some("❗ This entry has no timestamp to load it by.".to_string());
+        return;
+    };
+    let filter = match serde_json::to_string(&doc! { "ts": ts }) {
+        Ok(filter) => filter,
+        Err(error) => {
+            state.popup_message = Some(format!("❌ Could not build filter: {error}"));
+            return;
+        }
+    };
+
+    let db = db.clone();
+    state.info_panel = None;
+    state.filter_text = filter;
+    state.current_documents.clear();
+    state.expanded_field = None;
+    state.document_skip = 0;
+    state.focus = FocusArea::Documents;
+    state.fetch_collection_data = Some((uri, db, "system.profile".to_string()));
+    state.popup_message_success = Some(format!(
+        "Opened profile entry (newest {PROFILE_ENTRY_LIMIT} are listed; P reopens the profiler)"
+    ));
+}
@@ -0,0 +1,117 @@
+use crate::app::{AppState, InfoPanel, PanelLine, PanelTone};
+use crate::db::replset::{LAG_WARNING_SECS, hello, replica_set_status};
+use mongodb::bson::{Bson, Document};
+
+fn row(label: &str, value: impl std::fmt::Display) -> PanelLine {
+    PanelLine::new(PanelTone::Normal, format!("  {label:<22}{value}"))
+}
+
+fn hello_lines(hello: &Document) -> Vec<PanelLine> {
+    let text = |key: &str| hello.get(key).map(ToString::to_string);
+    let mut lines = vec![PanelLine::new(PanelTone::Header, "hello")];
+    for key in [
+        "setName",
+        "isWritablePrimary",
+        "secondary",
+        "primary",
+        "me",
+        "setVersion",
+        "maxWireVersion",
+    ] {
+        if let Some(value) = text(key) {
+            lines.push(row(key, value));
+        }
+    }
+    if let Ok(hosts) = hello.get_array("hosts") {
+        let hosts: Vec<&str> = hosts.iter().filter_map(Bson::as_str).collect();
+        lines.push(row("hosts", hosts.join(", ")));
+    }
+    lines
+}
+
+/// Shows topology from `hello` and, on a replica set, the members from
+/// `replSetGetStatus` with lagging or unhealthy ones highlighted.
+pub async fn open_replset_panel(state: &mut AppState) {
+    let Some(client) = state.mongo_client.clone() else {
+        state.popup_message = Some("❌ No active MongoDB connection.".to_string());
+        return;
+    };
+
+    let hello = match hello(&client).await {
+        Ok(hello) => hello,
+        Err(error) => {
+            state.popup_message = Some(format!("❌ Could not run hello: {error}"));
+            return;
+        }
+    };
+    let mut lines = hello_lines(&hello);
+    lines.push(PanelLine::new(PanelTone::Normal, ""));
+
+    let title = if hello.contains_key("setName") {
+        match replica_set_status(&client).await {
+            Ok(status) => {
+                lines.push(PanelLine::new(
+                    PanelTone::Header,
+                    format!(
+                        "{:<28} {:<12} {:<8} {:<26} {:>8}  sync source",
+                        "member", "state", "health", "optime", "lag"
+                    ),
+                ));
+                for member in &status.members {
+                    let tone = if member.needs_attention() {
+                        PanelTone::Removed
+                    } else {
+                        PanelTone::Normal
+                    };
+                    let name = if member.is_self {
+                        format!("{} (self)", member.name)
+                    } else {
+                        member.name.clone()
+                    };
+                    let optime = member
+                        .optime
+                        .and_then(|optime| optime.try_to_rfc3339_string().ok())
+                        .unwrap_or_else(|| "-".to_string());
+                    let lag = member
+                        .lag_secs
+                        .map(|lag| format!("{lag}s"))
+                        .unwrap_or_else(|| "-".to_string());
+                    let health = if member.healthy { "ok" } else { "down" };
+                    lines.push(PanelLine::new(
+                        tone,
+                        format!(
+                            "{name:<28} {:<12} {health:<8} {optime:<26} {lag:>8}  {}",
+                            member.state, member.sync_source
+                        ),
+                    ));
+                }
+                let attention = status
+                    .members
+                    .iter()
+                    .filter(|member| member.needs_attention())
+                    .count();
+                format!(
+                    "Replica set {} · {} member(s), {attention} need attention (lag > {LAG_WARNING_SECS}s or unhealthy) · Esc: close",
+                    status.set,
+                    status.members.len()
+                )
+            }
+            Err(error) => {
+                lines.push(PanelLine::new(
+                    PanelTone::Removed,
+                    format!("⚠️ Could not run replSetGetStatus: {error}"),
+                ));
+                "Replica set · Esc: close".to_string()
+            }
+        }
+    } else {
+        lines.push(PanelLine::new(
+            PanelTone::Muted,
+            "Not a replica set member: replSetGetStatus is unavailable.",
+        ));
+        "Topology · Esc: close".to_string()
+    };
+
+    state.popup_message = None;
+    state.info_panel = Some(InfoPanel::new(title, lines));
+}
@@ -0,0 +1,101 @@
+use crate::app::{AppState, InfoPanel, PanelKind, PanelLine, PanelTone};
+use crate::db::schema::{analyze_documents, sample_documents, to_json_schema};
+use crossterm::event::{KeyCode, KeyEvent};
+use mongodb::bson::Document;
+
+/// Samples documents (or reads them under `filter`) and shows the observed
+/// field paths with their types, presence and example values.
+pub async fn open_schema_panel(
+    state: &mut AppState,
+    db: &str,
+    collection: &str,
+    filter: Option<Document>,
+    size: i64,
+) -> Result<(), String> {
+    let client = state
+        .mongo_client
+        .clone()
+        .ok_or_else(|| "No active MongoDB connection.".to_string())?;
+    let source = if filter.is_some() {
+        "under the current filter"
+    } else {
+        "sampled"
+    };
+    let documents = sample_documents(&client, db, collection, filter, size)
+        .await
+        .map_err(|error| format!("Could not sample documents: {error}"))?;
+    let report = analyze_documents(&documents);
+
+    let mut lines = Vec::new();
+    for (path, summary) in &report.fields {
+        let presence = summary.documents as f64 * 100.0 / report.documents.max(1) as f64;
+        let occurrences = summary.occurrences().max(1) as f64;
+        let types = summary
+            .types
+            .iter()
+            .map(|(name, count)| format!("{name} {:.1}%", *count as f64 * 100.0 / occurrences))
+            .collect::<Vec<_>>()
+            .join(" · ");
+        lines.push(PanelLine::new(
+            PanelTone::Normal,
+            format!("{path:<32} {presence:>5.1}% present   {types}"),
+        ));
+        if !summary.examples.is_empty() {
+            lines.push(PanelLine::new(
+                PanelTone::Muted,
+                format!("    e.g. {}", summary.examples.join(", ")),
+            ));
+        }
+    }
+    if report.fields.is_empty() {
+        lines.push(PanelLine::new(PanelTone::Muted, "No documents to analyze."));
+    }
+
+    state.popup_message = None;
+    state.info_panel = Some(InfoPanel {
+        kind: PanelKind::Schema {
+            db: db.to_string(),
+            collection: collection.to_string(),
+            json_schema: to_json_schema(&report),
+        },
+        ..InfoPanel::new(
+            format!(
+                "Schema: {db}.{collection} · {} document(s) {source} · x: export JSON Schema · Esc: close",
+                report.documents
+            ),
+            lines,
+        )
+    });
+    Ok(())
+}
+
+pub fn handle_schema_panel_key(key: KeyEvent, state: &mut AppState) {
+    let Some(InfoPanel {
+        kind:
+            PanelKind::Schema {
+                db,
+                collection,
+                json_schema,
+            },
+        ..
+    }) = &state.info_panel
+    else {
+        return;
+    };
+
+    if key.code != KeyCode::Char('x') {
+        return;
+    }
+
+    let path = crate::utils::get_data_dir().join(format!("{db}_{collection}.schema.json"));
+    let written = serde_json::to_string_pretty(json_schema)
+        .map_err(|error| error.to_string())
+        .and_then(|json| std::fs::write(&path, json).map_err(|error| error.to_string()));
+    match written {
+        Ok(()) => {
+            state.popup_message_success =
+                Some(format!("✅ Exported JSON Schema: {}", path.display()))
+        }
+        Err(error) => state.popup_message = Some(format!("❌ Failed to export schema: {error}")),
+    }
+}
@@ -0,0 +1,76 @@
+use crate::app::{AppState, InfoPanel, PanelLine, PanelTone};
+use crate::db::stats::{collection_stats, database_stats};
+use crate::utils::format_bytes;
+
+fn row(label: &str, value: impl std::fmt::Display) -> PanelLine {
+    PanelLine::new(PanelTone::Normal, format!("  {label:<18}{value}"))
+}
+
+fn yes_no(value: bool) -> &'static str {
+    if value { "yes" } else { "no" }
+}
+
+/// Shows `collStats` for a collection, or `dbStats` when `collection` is `None`.
+pub async fn open_stats_panel(state: &mut AppState, db: &str, collection: Option<&str>) {
+    let Some(client) = state.mongo_client.clone() else {
+        state.popup_message = Some("❌ No active MongoDB connection.".to_string());
+        return;
+    };
+
+    let mut lines = Vec::new();
+    let title = match collection {
+        Some(collection) => {
+            let stats = match collection_stats(&client, db, collection).await {
+                Ok(stats) => stats,
+                Err(error) => {
+                    state.popup_message = Some(format!("❌ Could not load collStats: {error}"));
+                    return;
+                }
+            };
+
+            lines.push(row("Documents", stats.count));
+            lines.push(row("Data size", format_bytes(stats.size)));
+            lines.push(row("Storage size", format_bytes(stats.storage_size)));
+            lines.push(row("Avg object size", format_bytes(stats.avg_obj_size)));
+            lines.push(row("Index size", format_bytes(stats.total_index_size)));
+            let capped = match (stats.capped, stats.max_size, stats.max_documents) {
+                (true, Some(size), Some(max)) if max > 0 => {
+                    format!("yes ({}, max {max} documents)", format_bytes(size))
+                }
+                (true, Some(size), _) => format!("yes ({})", format_bytes(size)),
+                (capped, _, _) => yes_no(capped).to_string(),
+            };
+            lines.push(row("Capped", capped));
+            lines.push(row("Time-series", yes_no(stats.timeseries)));
+
+            lines.push(PanelLine::new(PanelTone::Normal, ""));
+            lines.push(PanelLine::new(PanelTone::Header, "Index sizes"));
+            for (name, size) in &stats.index_sizes {
+                lines.push(row(name, format_bytes(*size)));
+            }
+            format!("collStats: {db}.{collection} · Esc: close")
+        }
+        None => {
+            let stats = match database_stats(&client, db).await {
+                Ok(stats) => stats,
+                Err(error) => {
+                    state.popup_message = Some(format!("❌ Could not load dbStats: {error}"));
+                    return;
+                }
+            };
+
+            lines.push(row("Collections", stats.collections));
+            lines.push(row("Views", stats.views));
+            lines.push(row("Documents", stats.objects));
+            lines.push(row("Data size", format_bytes(stats.data_size)));
+            lines.push(row("Storage size", format_bytes(stats.storage_size)));
+            lines.push(row("Avg object size", format_bytes(stats.avg_obj_size)));
+            lines.push(row("Indexes", stats.indexes));
+            lines.push(row("Index size", format_bytes(stats.index_size)));
+            format!("dbStats: {db} · Esc: close")
+        }
+    };
+
+    state.popup_message = None;
+    state.info_panel = Some(InfoPanel::new(title, lines));
+}
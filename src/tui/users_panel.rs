@@ -0,0 +1,171 @@
+use crate::app::{AppState, InfoPanel, PanelKind, PanelLine, PanelTone, PendingDeletion};
+use crate::db::users::{list_roles, list_users};
+use crate::tui::form::{FormField, FormPurpose, FormState};
+use crossterm::event::{KeyCode, KeyEvent};
+
+fn join<T: ToString>(items: &[T]) -> String {
+    items
+        .iter()
+        .map(ToString::to_string)
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Lists the users of a database (`usersInfo`) followed by its custom and
+/// built-in roles with their privileges (`rolesInfo`).
+pub async fn open_users_panel(state: &mut AppState, db: &str) {
+    let Some(client) = state.mongo_client.clone() else {
+        state.popup_message = Some("❌ No active MongoDB connection.".to_string());
+        return;
+    };
+
+    let users = match list_users(&client, db).await {
+        Ok(users) => users,
+        Err(error) => {
+            state.popup_message = Some(format!("❌ Could not load usersInfo: {error}"));
+            return;
+        }
+    };
+
+    let mut lines = vec![PanelLine::new(PanelTone::Header, "Users")];
+    let mut items = Vec::new();
+    for user in &users {
+        items.push(lines.len());
+        lines.push(PanelLine::new(PanelTone::Normal, user.user.clone()));
+        let roles = match user.roles.as_slice() {
+            [] => "no roles".to_string(),
+            roles => join(roles),
+        };
+        lines.push(PanelLine::new(
+            PanelTone::Muted,
+            format!("    roles: {roles}"),
+        ));
+        if !user.mechanisms.is_empty() {
+            lines.push(PanelLine::new(
+                PanelTone::Muted,
+                format!("    mechanisms: {}", user.mechanisms.join(", ")),
+            ));
+        }
+    }
+    if users.is_empty() {
+        lines.push(PanelLine::new(PanelTone::Muted, "No users."));
+    }
+
+    lines.push(PanelLine::new(PanelTone::Normal, ""));
+    match list_roles(&client, db).await {
+        Ok(roles) => {
+            lines.push(PanelLine::new(PanelTone::Header, "Roles"));
+            for role in &roles {
+                let kind = if role.built_in { "built-in" } else { "custom" };
+                lines.push(PanelLine::new(
+                    PanelTone::Normal,
+                    format!("{} ({kind})", role.role),
+                ));
+                if !role.roles.is_empty() {
+                    lines.push(PanelLine::new(
+                        PanelTone::Muted,
+                        format!("    inherits: {}", join(&role.roles)),
+                    ));
+                }
+                for (resource, actions) in &role.privileges {
+                    lines.push(PanelLine::new(
+                        PanelTone::Muted,
+                        format!("    {resource}: {}", actions.join(", ")),
+                    ));
+                }
+            }
+        }
+        Err(error) => lines.push(PanelLine::new(
+            PanelTone::Removed,
+            format!("⚠️ Could not load rolesInfo: {error}"),
+        )),
+    }
+
+    let selected = match &state.info_panel {
+        Some(InfoPanel {
+            kind: PanelKind::Users { .. },
+            selected,
+            ..
+        }) => (*selected).min(items.len().saturating_sub(1)),
+        _ => 0,
+    };
+    let mut panel = InfoPanel::selectable(
+        format!(
+            "Users: {db} · a: add · g: grant · x: revoke · p: password · d: drop · r: refresh · Esc: close"
+        ),
+        lines,
+        PanelKind::Users {
+            db: db.to_string(),
+            users: users.into_iter().map(|user| user.user).collect(),
+        },
+        items,
+    );
+    panel.selected = selected;
+    state.info_panel = Some(panel);
+}
+
+pub async fn handle_users_panel_key(key: KeyEvent, state: &mut AppState) {
+    let Some(InfoPanel {
+        kind: PanelKind::Users { db, users },
+        selected,
+        ..
+    }) = &state.info_panel
+    else {
+        return;
+    };
+    let db = db.clone();
+    let user = users.get(*selected).cloned();
+
+    match key.code {
+        KeyCode::Char('r') => open_users_panel(state, &db).await,
+        KeyCode::Char('a') => {
+            state.form = Some(FormState::new(
+                format!("New user in {db}"),
+                FormPurpose::CreateUser { db },
+                vec![
+                    FormField::text("User", ""),
+                    FormField::secret("Password"),
+                    FormField::secret("Confirm"),
+                    FormField::text("Roles", "read"),
+                ],
+            ));
+        }
+        KeyCode::Char('g') | KeyCode::Char('x') | KeyCode::Char('p') | KeyCode::Char('d')
+            if user.is_none() =>
+        {
+            state.popup_message = Some("❗ Select a user.".to_string());
+        }
+        KeyCode::Char('g') | KeyCode::Char('x') => {
+            let grant = key.code == KeyCode::Char('g');
+            let user = user.unwrap_or_default();
+            let action = if grant {
+                "Grant roles to"
+            } else {
+                "Revoke roles from"
+            };
+            state.form = Some(FormState::new(
+                format!("{action} {user}@{db}"),
+                FormPurpose::SetRoles { db, user, grant },
+                vec![FormField::text("Roles", "")],
+            ));
+        }
+        KeyCode::Char('p') => {
+            let user = user.unwrap_or_default();
+            state.form = Some(FormState::new(
+                format!("Change password of {user}@{db}"),
+                FormPurpose::ChangePassword { db, user },
+                vec![FormField::secret("Password"), FormField::secret("Confirm")],
+            ));
+        }
+        KeyCode::Char('d') => {
+            let deletion = PendingDeletion::User {
+                db,
+                name: user.unwrap_or_default(),
+            };
+            state.popup_message_success = None;
+            state.popup_message = Some(deletion.confirmation_message());
+            state.pending_deletion = Some(deletion);
+        }
+        _ => {}
+    }
+}
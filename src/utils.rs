@@ -232,6 +232,35 @@ pub fn resolve_connection_uri_by_stored_uri(
         Ok(uri.to_string())
     }
 }
+/// The scheme and hosts of a connection URI, lowercased and sorted with the
+/// default port filled in, so URIs that differ only in credentials, options
+/// or host order compare equal.
+pub fn server_key(uri: &str) -> String {
+    let (scheme, rest) = uri.split_once("://").unwrap_or(("mongodb", uri));
+    let scheme = scheme.to_ascii_lowercase();
+    let authority = &rest[..rest.find(['/', '?', '#']).unwrap_or(rest.len())];
+    let hosts = authority
+        .rsplit_once('@')
+        .map_or(authority, |(_, hosts)| hosts);
+
+    let mut hosts: Vec<String> = hosts
+        .split(',')
+        .map(|host| {
+            let host = host.trim().to_ascii_lowercase();
+            let has_port = host
+                .rfind(':')
+                .is_some_and(|colon| host.rfind(']').is_none_or(|bracket| colon > bracket));
+            if has_port || scheme == "mongodb+srv" {
+                host
+            } else {
+                format!("{host}:27017")
+            }
+        })
+        .collect();
+    hosts.sort();
+    format!("{scheme}://{}", hosts.join(","))
+}
+
 pub fn parse_connection_input(input: &str) -> Option<(String, String)> {
     let parts: Vec<&str> = input.trim().split(';').map(|s| s.trim()).collect();
 
@@ -324,3 +353,49 @@ pub fn write_clipboard_string(text: &str) -> Result<(), String> {
         ))
     }
 }
+
+pub fn format_bytes(bytes: i64) -> String {
+    const UNITS: [&str; 5] = ["B", "KB", "MB", "GB", "TB"];
+    let mut value = bytes as f64;
+    let mut unit = 0;
+    while value.abs() >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{bytes} B")
+    } else {
+        format!("{value:.1} {}", UNITS[unit])
+    }
+}
+
+pub fn format_count(count: i64) -> String {
+    let value = count as f64;
+    if count < 1_000 {
+        count.to_string()
+    } else if count < 1_000_000 {
+        format!("{:.1}k", value / 1_000.0)
+    } else if count < 1_000_000_000 {
+        format!("{:.1}M", value / 1_000_000.0)
+    } else {
+        format!("{:.1}B", value / 1_000_000_000.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::server_key;
+
+    #[test]
+    fn server_key_ignores_credentials_options_and_host_order() {
+        assert_eq!(
+            server_key("mongodb://user:pw@B.example:27018,a.example/db?replicaSet=rs"),
+            server_key("mongodb://a.example:27017,b.example:27018"),
+        );
+        assert_eq!(server_key("mongodb://[::1]"), "mongodb://[::1]:27017");
+        assert_ne!(
+            server_key("mongodb://localhost:27017"),
+            server_key("mongodb://localhost:27018")
+        );
+    }
+}
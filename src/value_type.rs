@@ -0,0 +1,128 @@
+use mongodb::bson::{Bson, DateTime, oid::ObjectId};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ValueType {
+    String,
+    Int,
+    Long,
+    Double,
+    Bool,
+    Date,
+    ObjectId,
+    Null,
+}
+
+impl ValueType {
+    pub const ALL: [Self; 8] = [
+        Self::String,
+        Self::Int,
+        Self::Long,
+        Self::Double,
+        Self::Bool,
+        Self::Date,
+        Self::ObjectId,
+        Self::Null,
+    ];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::String => "string",
+            Self::Int => "int",
+            Self::Long => "long",
+            Self::Double => "double",
+            Self::Bool => "bool",
+            Self::Date => "date",
+            Self::ObjectId => "objectId",
+            Self::Null => "null",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|value_type| value_type.as_str() == value.trim())
+    }
+
+    pub fn of(value: &Bson) -> Option<Self> {
+        match value {
+            Bson::String(_) => Some(Self::String),
+            Bson::Int32(_) => Some(Self::Int),
+            Bson::Int64(_) => Some(Self::Long),
+            Bson::Double(_) => Some(Self::Double),
+            Bson::Boolean(_) => Some(Self::Bool),
+            Bson::DateTime(_) => Some(Self::Date),
+            Bson::ObjectId(_) => Some(Self::ObjectId),
+            Bson::Null => Some(Self::Null),
+            _ => None,
+        }
+    }
+
+    pub fn parse_value(self, text: &str) -> Result<Bson, String> {
+        let trimmed = text.trim();
+        match self {
+            Self::String => Ok(Bson::String(text.to_string())),
+            Self::Int => trimmed
+                .parse::<i32>()
+                .map(Bson::Int32)
+                .map_err(|_| format!("'{trimmed}' is not a 32-bit integer.")),
+            Self::Long => trimmed
+                .parse::<i64>()
+                .map(Bson::Int64)
+                .map_err(|_| format!("'{trimmed}' is not a 64-bit integer.")),
+            Self::Double => trimmed
+                .parse::<f64>()
+                .map(Bson::Double)
+                .map_err(|_| format!("'{trimmed}' is not a number.")),
+            Self::Bool => match trimmed.to_ascii_lowercase().as_str() {
+                "true" | "1" | "yes" => Ok(Bson::Boolean(true)),
+                "false" | "0" | "no" => Ok(Bson::Boolean(false)),
+                _ => Err(format!("'{trimmed}' is not a boolean.")),
+            },
+            Self::Date => DateTime::parse_rfc3339_str(trimmed)
+                .or_else(|_| DateTime::parse_rfc3339_str(format!("{trimmed}T00:00:00Z")))
+                .map(Bson::DateTime)
+                .map_err(|_| format!("'{trimmed}' is not an RFC 3339 date.")),
+            Self::ObjectId => ObjectId::parse_str(trimmed)
+                .map(Bson::ObjectId)
+                .map_err(|_| format!("'{trimmed}' is not a valid ObjectId.")),
+            Self::Null => Ok(Bson::Null),
+        }
+    }
+}
+
+/// Renders a scalar the way it should be typed back into `ValueType::parse_value`.
+pub fn editable_text(value: &Bson) -> String {
+    match value {
+        Bson::String(text) => text.clone(),
+        Bson::DateTime(date) => date
+            .try_to_rfc3339_string()
+            .unwrap_or_else(|_| date.to_string()),
+        Bson::ObjectId(oid) => oid.to_hex(),
+        Bson::Null => String::new(),
+        other => other.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ValueType, editable_text};
+    use mongodb::bson::{Bson, DateTime};
+
+    #[test]
+    fn parses_each_type() {
+        assert_eq!(ValueType::Int.parse_value("42"), Ok(Bson::Int32(42)));
+        assert_eq!(ValueType::Long.parse_value("42"), Ok(Bson::Int64(42)));
+        assert_eq!(ValueType::Double.parse_value("1.5"), Ok(Bson::Double(1.5)));
+        assert_eq!(ValueType::Bool.parse_value("yes"), Ok(Bson::Boolean(true)));
+        assert_eq!(ValueType::Null.parse_value("ignored"), Ok(Bson::Null));
+        assert!(ValueType::Int.parse_value("4.2").is_err());
+        assert!(ValueType::ObjectId.parse_value("nope").is_err());
+    }
+
+    #[test]
+    fn round_trips_dates_through_editable_text() {
+        let date = Bson::DateTime(DateTime::from_millis(1_700_000_000_000));
+        assert_eq!(ValueType::Date.parse_value(&editable_text(&date)), Ok(date));
+        assert!(ValueType::Date.parse_value("2024-05-01").is_ok());
+    }
+}
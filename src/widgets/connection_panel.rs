@@ -1,4 +1,5 @@
 use crate::app::{AppState, FocusArea, SelectableItem};
+use crate::utils::{format_bytes, format_count};
 use ratatui::{
     Frame,
     layout::Rect,
@@ -29,8 +30,38 @@ pub fn render_connections(f: &mut Frame, area: Rect, state: &AppState) {
             SelectableItem::Database { name, .. } => {
                 ListItem::new(Line::from(format!("  └ {}", name)))
             }
-            SelectableItem::Collection { name, .. } => {
-                ListItem::new(Line::from(format!("      • {}", name)))
+            SelectableItem::Collection { uri, db, name }
+                if state
+                    .views
+                    .contains_key(&(uri.clone(), db.clone(), name.clone())) =>
+            {
+                let view_on = &state.views[&(uri.clone(), db.clone(), name.clone())].view_on;
+                ListItem::new(Line::from(vec![
+                    Span::styled(
+                        format!("      ◇ {}", name),
+                        Style::default().fg(theme.accent),
+                    ),
+                    Span::styled(
+                        format!("  view on {view_on}"),
+                        Style::default().fg(theme.muted),
+                    ),
+                ]))
+            }
+            SelectableItem::Collection { uri, db, name } => {
+                let hint = state
+                    .collection_hints
+                    .get(&(uri.clone(), db.clone(), name.clone()))
+                    .filter(|_| state.show_tree_hints);
+                match hint {
+                    Some((count, size)) => ListItem::new(Line::from(vec![
+                        Span::raw(format!("      • {}", name)),
+                        Span::styled(
+                            format!("  {} · {}", format_count(*count), format_bytes(*size)),
+                            Style::default().fg(theme.muted),
+                        ),
+                    ])),
+                    None => ListItem::new(Line::from(format!("      • {}", name))),
+                }
             }
         })
         .collect();
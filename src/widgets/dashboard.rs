@@ -0,0 +1,152 @@
+use crate::db::server_status::OPCOUNTER_KINDS;
+use crate::theme::Theme;
+use crate::tui::dashboard::{DASHBOARD_INTERVAL, Dashboard};
+use crate::utils::format_bytes;
+use crate::widgets::import::centered_rect;
+use ratatui::{
+    Frame,
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Color, Style},
+    widgets::{Block, BorderType, Borders, Clear, Paragraph, Sparkline},
+};
+
+fn sparkline<'a>(title: String, data: &'a [u64], color: Color, theme: &Theme) -> Sparkline<'a> {
+    Sparkline::default()
+        .block(
+            Block::default()
+                .title(title)
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(theme.muted)),
+        )
+        .data(data)
+        .style(Style::default().fg(color))
+}
+
+/// Keeps the newest points that fit in `area`, since sparklines draw from
+/// the left.
+fn fit(data: Vec<u64>, area: Rect) -> Vec<u64> {
+    let width = area.width.saturating_sub(2) as usize;
+    data[data.len().saturating_sub(width)..].to_vec()
+}
+
+pub fn render_dashboard(f: &mut Frame, area: Rect, dashboard: &Dashboard, theme: &Theme) {
+    let popup_area = centered_rect(80, 80, area);
+    let block = Block::default()
+        .title(format!(
+            " serverStatus · every {}s · Esc: close ",
+            DASHBOARD_INTERVAL.as_secs()
+        ))
+        .borders(Borders::ALL)
+        .border_type(BorderType::Rounded)
+        .border_style(Style::default().fg(theme.primary))
+        .style(Style::default().fg(theme.foreground).bg(theme.background));
+    let inner = block.inner(popup_area);
+    f.render_widget(Clear, popup_area);
+    f.render_widget(block, popup_area);
+
+    let Some(latest) = dashboard.latest() else {
+        let message = dashboard
+            .error
+            .clone()
+            .map(|error| format!("❌ {error}"))
+            .unwrap_or_else(|| "Waiting for the first sample…".to_string());
+        f.render_widget(Paragraph::new(message), inner);
+        return;
+    };
+    let last_rate = |rates: &[u64]| rates.last().copied().unwrap_or(0);
+
+    let rows = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(1),
+            Constraint::Ratio(1, 5),
+            Constraint::Ratio(1, 5),
+            Constraint::Ratio(1, 5),
+            Constraint::Ratio(1, 5),
+            Constraint::Ratio(1, 5),
+        ])
+        .split(inner);
+
+    let status = match &dashboard.error {
+        Some(error) => {
+            Paragraph::new(format!("⚠️ {error}")).style(Style::default().fg(theme.error))
+        }
+        None => Paragraph::new(format!("{} sample(s)", dashboard.samples.len()))
+            .style(Style::default().fg(theme.muted)),
+    };
+    f.render_widget(status, rows[0]);
+
+    let ops = fit(dashboard.rates(|sample| sample.total_ops()), rows[1]);
+    let per_kind: Vec<String> = OPCOUNTER_KINDS
+        .iter()
+        .enumerate()
+        .map(|(index, kind)| {
+            let rates = dashboard.rates(|sample| sample.opcounters[index]);
+            format!("{kind} {}", last_rate(&rates))
+        })
+        .collect();
+    let title = format!(
+        " opcounters {}/s · {} ",
+        last_rate(&ops),
+        per_kind.join(" · ")
+    );
+    f.render_widget(sparkline(title, &ops, theme.primary, theme), rows[1]);
+
+    let connections = fit(dashboard.series(|sample| sample.connections), rows[2]);
+    let title = format!(
+        " connections {} current · {} available ",
+        latest.connections, latest.available_connections
+    );
+    f.render_widget(
+        sparkline(title, &connections, theme.secondary, theme),
+        rows[2],
+    );
+
+    let network = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Ratio(1, 2), Constraint::Ratio(1, 2)])
+        .split(rows[3]);
+    let bytes_in = fit(dashboard.rates(|sample| sample.bytes_in), network[0]);
+    let bytes_out = fit(dashboard.rates(|sample| sample.bytes_out), network[1]);
+    let title = format!(
+        " network in {}/s ",
+        format_bytes(last_rate(&bytes_in) as i64)
+    );
+    f.render_widget(
+        sparkline(title, &bytes_in, theme.success, theme),
+        network[0],
+    );
+    let title = format!(
+        " network out {}/s ",
+        format_bytes(last_rate(&bytes_out) as i64)
+    );
+    f.render_widget(
+        sparkline(title, &bytes_out, theme.success, theme),
+        network[1],
+    );
+
+    let cache = fit(
+        dashboard.series(|sample| sample.cache_percent() as i64),
+        rows[4],
+    );
+    let title = format!(
+        " WiredTiger cache {}% · {} of {} ",
+        latest.cache_percent(),
+        format_bytes(latest.cache_bytes),
+        format_bytes(latest.cache_max_bytes)
+    );
+    f.render_widget(
+        sparkline(title, &cache, theme.accent, theme).max(100),
+        rows[4],
+    );
+
+    let queued = fit(
+        dashboard.series(|sample| sample.queued_readers + sample.queued_writers),
+        rows[5],
+    );
+    let title = format!(
+        " queued readers {} · writers {} ",
+        latest.queued_readers, latest.queued_writers
+    );
+    f.render_widget(sparkline(title, &queued, theme.error, theme), rows[5]);
+}
@@ -0,0 +1,120 @@
+use crate::{
+    theme::Theme,
+    tui::form::{FormFieldKind, FormState},
+};
+use ratatui::{
+    Frame,
+    layout::Rect,
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, BorderType, Borders, Clear, Paragraph},
+};
+use unicode_width::UnicodeWidthStr;
+
+const LABEL_WIDTH: usize = 16;
+
+pub fn render_form(f: &mut Frame, area: Rect, form: &FormState, theme: &Theme) {
+    let width = (area.width * 3 / 5).max(40).min(area.width);
+    let preview_height = if form.preview.is_empty() {
+        0
+    } else {
+        form.preview.len() as u16 + 1
+    };
+    let height = (form.fields.len() as u16 + preview_height + 4).min(area.height);
+    let form_area = Rect {
+        x: area.x + (area.width - width) / 2,
+        y: area.y + (area.height - height) / 3,
+        width,
+        height,
+    };
+    let inner_width = width.saturating_sub(2) as usize;
+    let value_width = inner_width.saturating_sub(LABEL_WIDTH + 1);
+
+    // When everything does not fit, the preview keeps its rows (leaving at
+    // least one for fields) and the field list scrolls to the selection.
+    let inner_height = height.saturating_sub(4) as usize;
+    let preview_rows = (preview_height as usize).min(inner_height.saturating_sub(1));
+    let field_rows = inner_height.saturating_sub(preview_rows).max(1);
+    let first_field = (form.selected + 1).saturating_sub(field_rows);
+
+    let mut lines: Vec<Line> = Vec::new();
+    let mut cursor = None;
+    for (index, field) in form
+        .fields
+        .iter()
+        .enumerate()
+        .skip(first_field)
+        .take(field_rows)
+    {
+        let is_selected = index == form.selected;
+        let label_style = if is_selected {
+            Style::default()
+                .fg(theme.accent)
+                .bg(theme.primary)
+                .add_modifier(Modifier::BOLD)
+        } else {
+            Style::default().fg(theme.muted)
+        };
+
+        let value = match &field.kind {
+            FormFieldKind::Text | FormFieldKind::Secret => {
+                let chars: Vec<char> = match field.kind {
+                    FormFieldKind::Secret => vec!['•'; field.value.chars().count()],
+                    _ => field.value.chars().collect(),
+                };
+                let position = if is_selected {
+                    form.cursor_position.min(chars.len())
+                } else {
+                    chars.len()
+                };
+                let skip = position.saturating_sub(value_width.saturating_sub(1));
+                let visible: String = chars.iter().skip(skip).take(value_width).collect();
+                if is_selected {
+                    let offset: String = chars[skip..position].iter().collect();
+                    cursor = Some((
+                        form_area.x + 1 + (LABEL_WIDTH + 1 + offset.width()) as u16,
+                        form_area.y + 1 + (index - first_field) as u16,
+                    ));
+                }
+                visible
+            }
+            FormFieldKind::Choice(_) => format!("◀ {} ▶", field.value),
+        };
+
+        lines.push(Line::from(vec![
+            Span::styled(format!("{:<LABEL_WIDTH$}", field.label), label_style),
+            Span::raw(" "),
+            Span::styled(value, Style::default().fg(theme.foreground)),
+        ]));
+    }
+    if preview_rows > 0 {
+        lines.push(Line::from(""));
+        lines.extend(
+            form.preview.iter().take(preview_rows - 1).map(|line| {
+                Line::from(Span::styled(line.clone(), Style::default().fg(theme.muted)))
+            }),
+        );
+    }
+    lines.push(Line::from(""));
+    lines.push(Line::from(Span::styled(
+        "Tab/↑↓ field · ←→ change option · Enter save · Esc cancel",
+        Style::default().fg(theme.muted),
+    )));
+
+    let paragraph = Paragraph::new(lines)
+        .block(
+            Block::default()
+                .title(format!(" {} ", form.title))
+                .borders(Borders::ALL)
+                .border_type(BorderType::Rounded)
+                .border_style(Style::default().fg(theme.primary)),
+        )
+        .style(Style::default().fg(theme.foreground).bg(theme.background));
+
+    f.render_widget(Clear, form_area);
+    f.render_widget(paragraph, form_area);
+
+    if let Some(position) = cursor {
+        f.set_cursor_position(position);
+    }
+}
@@ -10,7 +10,13 @@ use ratatui::{
 pub const HELP_TEXT: &str = r#"
 Global:
 ?           Toggle help popup
+M           serverStatus dashboard with sparklines (Esc: close)
+O           currentOp monitor (x: kill, f: filter by namespace/min ms, p: pause)
+P           Profiler for the database (l: level/slowms, s: sort, g: group by shape, Enter: open)
+H           Replica set status and topology (lagging/unhealthy members highlighted)
 t           Cycle theme (system, emerald, ocean, rose, monochrome)
+b           Toggle staging (buffer edits, deletes and clones)
+B           Review staged changes (y: commit in one transaction, x: discard)
 y / n       Confirm / cancel a pending action
 q           Quit the application
 Esc         Dismiss popup / clear search hits
@@ -26,14 +32,24 @@ Enter       Connect / expand database / load collection
 
 Connections & Collections:
 o           Add new MongoDB connection
+a           Create database (on a connection) or collection (on a database)
 /           Search collections
 n / N       Next / previous collection search match
-e           Edit selected connection or collection name
-x           Export selected collection or database
-i           Import collection into selected database
+e           Edit selected connection, database or collection name
+x           Export selected database, or a collection as JSON, CSV or TSV
+c           Copy selected collection or database (pick target, p: options)
+i           Import collection into selected database (JSON lines, or CSV/TSV with column mapping)
 I           Import database into selected connection
 f           Run shell script from file picker
 d+d         Request deletion of selected collection or database
+K           Index panel for selected collection (a/e: add, d: drop, h: hide)
+R           Unused and redundant index report for collection or database
+S           Statistics for selected collection or database
+v           Show viewOn and pipeline of the selected view
+T           Empty selected collection, keeping indexes and options (Esc stops)
+V           Edit validation rules in $EDITOR (v: find violators, y: apply)
+u           Users and roles of the database (a: add, g/x: grant/revoke, p: password, d: drop)
+z           Toggle document count and size hints in the tree
 
 Documents:
 /           Edit filter command
@@ -43,7 +59,14 @@ Ctrl+u/PageUp Scroll document up
 Enter       Expand/collapse selected field
 n / N       Next / previous field in selected document
 e           Edit selected document in external editor
-U           Edit a bulk update for filtered documents in $EDITOR
+c           Edit selected field value inline (with type selector)
+C           Clone selected document (t: pick target, p: paste there)
+R           Rename, convert or remove selected field across the collection
+A           Analyze schema from a sample (x: export JSON Schema)
+E           Export the shown documents (filter, sort, projection or pipeline, limit, format, file)
+W           Create a view from the filter/sort/projection or a pipeline
+U           Edit a bulk update (operators or pipeline array) in $EDITOR
+p           Preview a pending bulk update on sample documents
 X           Request deletion of filtered documents
 y           Copy selected field as filter fragment
 d+d         Request deletion of selected document
@@ -57,6 +80,12 @@ Backspace   Delete character
 Ctrl+V      Paste clipboard
 Ctrl+Shift+V Paste from terminal
 
+Forms:
+Tab / ↑ / ↓ Move between fields
+← / →       Move cursor / change option
+Enter       Save
+Esc         Cancel
+
 File Picker (import/export/script):
 j / k       Navigate entries
 Space       Select/Deselect file
@@ -0,0 +1,71 @@
+use crate::{
+    app::{InfoPanel, PanelTone},
+    theme::Theme,
+    widgets::import::centered_rect,
+};
+use ratatui::{
+    Frame,
+    layout::Rect,
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, BorderType, Borders, Clear, Paragraph},
+};
+
+fn tone_style(tone: PanelTone, theme: &Theme) -> Style {
+    match tone {
+        PanelTone::Normal => Style::default().fg(theme.foreground),
+        PanelTone::Header => Style::default()
+            .fg(theme.primary)
+            .add_modifier(Modifier::BOLD),
+        PanelTone::Added => Style::default().fg(theme.success),
+        PanelTone::Removed => Style::default().fg(theme.error),
+        PanelTone::Muted => Style::default().fg(theme.muted),
+    }
+}
+
+pub fn render_info_panel(f: &mut Frame, area: Rect, panel: &mut InfoPanel, theme: &Theme) {
+    let popup_area = centered_rect(80, 80, area);
+    let max_height = popup_area.height.saturating_sub(2) as usize;
+
+    let selected = panel.selected_line();
+    if let Some(line) = selected {
+        if line < panel.scroll {
+            panel.scroll = line;
+        } else if line >= panel.scroll + max_height {
+            panel.scroll = line + 1 - max_height;
+        }
+    }
+
+    let selected_style = Style::default()
+        .fg(theme.accent)
+        .bg(theme.primary)
+        .add_modifier(Modifier::BOLD);
+    let lines: Vec<Line> = panel
+        .lines
+        .iter()
+        .enumerate()
+        .skip(panel.scroll)
+        .take(max_height)
+        .map(|(index, line)| {
+            let style = if selected == Some(index) {
+                selected_style
+            } else {
+                tone_style(line.tone, theme)
+            };
+            Line::from(Span::styled(line.text.clone(), style))
+        })
+        .collect();
+
+    let paragraph = Paragraph::new(lines)
+        .block(
+            Block::default()
+                .title(format!(" {} ", panel.title))
+                .borders(Borders::ALL)
+                .border_type(BorderType::Rounded)
+                .border_style(Style::default().fg(theme.primary)),
+        )
+        .style(Style::default().fg(theme.foreground).bg(theme.background));
+
+    f.render_widget(Clear, popup_area);
+    f.render_widget(paragraph, popup_area);
+}
@@ -1,6 +1,10 @@
 pub mod connection_panel;
+pub mod dashboard;
 pub mod documents;
+pub mod form;
 pub mod help_popup;
 pub mod import;
+pub mod info_panel;
 pub mod popup;
+pub mod progress;
 pub mod toolbar;
@@ -0,0 +1,43 @@
+use crate::{theme::Theme, widgets::import::centered_rect};
+use ratatui::{
+    Frame,
+    layout::Rect,
+    style::Style,
+    widgets::{Block, BorderType, Borders, Clear, Gauge},
+};
+
+/// Draws a centered progress bar for a long-running operation.
+pub fn render_progress(
+    f: &mut Frame,
+    area: Rect,
+    title: &str,
+    done: u64,
+    total: u64,
+    theme: &Theme,
+) {
+    let rect = centered_rect(60, 10, area);
+    let popup_area = Rect {
+        height: rect.height.max(3),
+        ..rect
+    };
+    let ratio = if total == 0 {
+        1.0
+    } else {
+        (done as f64 / total as f64).min(1.0)
+    };
+
+    let gauge = Gauge::default()
+        .block(
+            Block::default()
+                .title(format!(" {title} · Esc: cancel "))
+                .borders(Borders::ALL)
+                .border_type(BorderType::Rounded)
+                .border_style(Style::default().fg(theme.primary)),
+        )
+        .gauge_style(Style::default().fg(theme.accent).bg(theme.background))
+        .ratio(ratio)
+        .label(format!("{done} / {total}"));
+
+    f.render_widget(Clear, popup_area);
+    f.render_widget(gauge, popup_area);
+}
@@ -102,6 +102,10 @@ pub fn render_status_bar(f: &mut Frame, area: Rect, state: &AppState) {
         }
     }
 
+    if let Some(changes) = &state.staged_changes {
+        status_line.push_str(&format!(" | staging: {} pending", changes.len()));
+    }
+
     let paragraph = Paragraph::new(Text::from(status_line))
         .style(Style::default().fg(theme.secondary))
         .block(Block::default());